@@ -1,58 +1,427 @@
+use config::{Config, Environment, File as ConfigFile, FileFormat};
 use serde::Deserialize;
 use tokio::runtime::Runtime;
 
+use std::collections::HashMap;
+use std::env;
 use std::error::Error;
-use std::fs::File;
-use std::io::Read;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
 
-use crate::core::rukinia_result::RukiniaError;
+use crate::core::plan::{rukinia_read_plan_lines, RukiniaPlanLine};
+use crate::core::rukinia_result::{ColorMode, RukiniaError};
+use crate::core::save_test_result::{ConsoleOutput, FormatOutput, ResultFormat};
 
-#[derive(Deserialize)]
+/// Settings file read from `/etc/rukinia/` and `$XDG_CONFIG_HOME/rukinia/`
+pub const SETTINGS_FILE: &str = "config.toml";
+
+/// Prefix of the environment variables overriding the settings, e.g. `RUKINIA_TOKIO__WORKER_THREADS`
+pub const SETTINGS_ENV_PREFIX: &str = "RUKINIA";
+
+/// Flavour of the tokio runtime running the tests.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeFlavor {
+    #[default]
+    CurrentThread,
+    MultiThread,
+}
+
+/// `[tokio]` section of the settings.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+#[serde(default)]
 pub struct TokioConfig {
-    pub _flavor: String,
-    pub _worker_threads: usize,
+    pub flavor: RuntimeFlavor,
+    /// Worker threads of a `multi_thread` runtime, tokio picks one per core when unset.
+    pub worker_threads: Option<usize>,
 }
 
-#[derive(Deserialize)]
+/// `[plan]` section of the settings.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct PlanConfig {
+    /// Test plans run when none is given on the command line.
+    pub paths: Vec<String>,
+}
+
+impl Default for PlanConfig {
+    fn default() -> Self {
+        PlanConfig {
+            paths: vec![DEFAULT_TEST_PLAN.to_string()],
+        }
+    }
+}
+
+/// `[output]` section of the settings.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Report written when no `--output` option is given on the command line.
+    pub format: Option<FormatOutput>,
+    /// Path of the reports, without extension.
+    pub path: String,
+    /// Reports written when no `--output` option is given, in addition to `format`.
+    pub reports: Vec<ResultFormat>,
+    pub console: ConsoleOutput,
+    /// Colour mode of the console output, overriding the `auto` default when set.
+    pub color: Option<ColorMode>,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        OutputConfig {
+            format: None,
+            path: "rukinia".to_string(),
+            reports: Vec::new(),
+            console: ConsoleOutput::Text,
+            color: None,
+        }
+    }
+}
+
+impl OutputConfig {
+    /// Returns the default path of a report in `format`: `path` with the extension of the format.
+    pub fn get_report_path(&self, format: &FormatOutput) -> String {
+        format!("{}.{}", self.path, format.extension())
+    }
+
+    /// Returns the reports to write when none is given on the command line.
+    pub fn get_reports(&self) -> Vec<ResultFormat> {
+        let mut reports = Vec::new();
+        if let Some(format) = &self.format {
+            reports.push(ResultFormat::new(
+                format.clone(),
+                self.get_report_path(format),
+            ));
+        }
+        reports.extend(self.reports.iter().cloned());
+        reports
+    }
+}
+
+/// `[tasks.http_request]` section of the settings.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct HttpRequestConfig {
+    /// Timeout of a request, in seconds.
+    pub timeout: u64,
+}
+
+impl Default for HttpRequestConfig {
+    fn default() -> Self {
+        HttpRequestConfig { timeout: 30 }
+    }
+}
+
+impl HttpRequestConfig {
+    pub fn get_timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+}
+
+/// `[tasks.plugins]` section of the settings.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct PluginsConfig {
+    /// Directories holding the `rukinia-task-<name>` executables, searched in this order.
+    pub paths: Vec<String>,
+    /// Time a plugin may run before being killed, in seconds.
+    pub timeout: u64,
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        PluginsConfig {
+            paths: vec![DEFAULT_PLUGIN_PATH.to_string()],
+            timeout: 30,
+        }
+    }
+}
+
+impl PluginsConfig {
+    pub fn get_timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+}
+
+/// `[tasks]` section of the settings: defaults applied by the tasks.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct TasksConfig {
+    pub http_request: HttpRequestConfig,
+    pub plugins: PluginsConfig,
+}
+
+/// Settings of rukinia, read from `config.toml`.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+#[serde(default)]
 pub struct RukiniaConfig {
-    pub _tokio: TokioConfig,
+    pub tokio: TokioConfig,
+    pub plan: PlanConfig,
+    pub output: OutputConfig,
+    pub tasks: TasksConfig,
 }
 
-pub fn rukinia_use_settings() -> Result<Runtime, Box<dyn Error>> {
-    // let settings = config::Config::builder()
-    //     .add_source(config::File::with_name("config"))
-    //     .build()?;
-    // let config: RukiniaConfig = settings.try_deserialize()?;
+static TASKS_CONFIG: OnceLock<TasksConfig> = OnceLock::new();
 
-    let runtime = tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()?;
+/// Where `rukinia_load_settings` reads the settings from.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct RukiniaSettingsSources {
+    /// Directory of the system settings, `/etc/rukinia`.
+    pub system_dir: PathBuf,
+    /// Configuration directory of the user, `$XDG_CONFIG_HOME` or `~/.config`, if known.
+    pub config_home: Option<PathBuf>,
+    /// Settings file given on the command line, if any.
+    pub cli_path: Option<PathBuf>,
+    /// The environment variables, the `RUKINIA_*` ones overriding the settings files.
+    pub environment: HashMap<String, String>,
+}
 
-    Ok(runtime)
+impl RukiniaSettingsSources {
+    /// Returns the sources of the settings of this process, read from its environment.
+    ///
+    /// # Arguments
+    /// * `cli_path` - Settings file given on the command line, if any.
+    pub fn from_process(cli_path: Option<&str>) -> Self {
+        let environment: HashMap<String, String> = env::vars().collect();
+        let config_home = environment
+            .get("XDG_CONFIG_HOME")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                environment
+                    .get("HOME")
+                    .map(|home| PathBuf::from(home).join(".config"))
+            });
+
+        RukiniaSettingsSources {
+            system_dir: PathBuf::from("/etc/rukinia"),
+            config_home,
+            cli_path: cli_path.map(PathBuf::from),
+            environment,
+        }
+    }
+
+    /// Returns the settings files read by `rukinia_load_settings`, lowest priority first.
+    pub fn get_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.system_dir.join(SETTINGS_FILE)];
+        if let Some(config_home) = &self.config_home {
+            paths.push(config_home.join("rukinia").join(SETTINGS_FILE));
+        }
+        paths.extend(self.cli_path.clone());
+        paths
+    }
 }
 
-pub fn rukinia_open_test_file(file: &mut Option<File>) {
-    *file = match File::open("/etc/rukinia/rukinia.conf") {
-        Ok(f) => Some(f),
-        Err(e) => {
+/// Loads the settings of rukinia.
+///
+/// `/etc/rukinia/config.toml`, `$XDG_CONFIG_HOME/rukinia/config.toml` and the
+/// file given on the command line are read in this order, each one overriding
+/// the previous ones, then the `RUKINIA_*` environment variables are applied
+/// (`RUKINIA_TOKIO__FLAVOR=multi_thread`, `RUKINIA_PLAN__PATHS=a.conf,b.conf`...).
+/// Missing files are ignored, except the one given on the command line.
+///
+/// # Arguments
+/// * `sources` - The settings files and environment, see `RukiniaSettingsSources::from_process`.
+///
+/// # Errors
+/// Returns a `RukiniaError` if a file cannot be read or holds invalid settings.
+///
+/// # Example
+/// ```rust,ignore
+/// let settings = rukinia_load_settings(&RukiniaSettingsSources::from_process(Some("board.toml")))?;
+/// ```
+pub fn rukinia_load_settings(
+    sources: &RukiniaSettingsSources,
+) -> Result<RukiniaConfig, RukiniaError> {
+    let mut builder = Config::builder();
+    for path in sources.get_paths() {
+        let required = sources.cli_path.as_ref() == Some(&path);
+        builder = builder.add_source(
+            ConfigFile::from(path)
+                .format(FileFormat::Toml)
+                .required(required),
+        );
+    }
+    builder = builder.add_source(
+        Environment::with_prefix(SETTINGS_ENV_PREFIX)
+            .prefix_separator("_")
+            .separator("__")
+            .try_parsing(true)
+            .list_separator(",")
+            .with_list_parse_key("plan.paths")
+            .with_list_parse_key("tasks.plugins.paths")
+            .source(Some(sources.environment.clone().into_iter().collect())),
+    );
+
+    builder
+        .build()
+        .and_then(|settings| settings.try_deserialize())
+        .map_err(|e| {
             RukiniaError::new(
-                "rukinia init open /etc/rukinia/rukinia.conf".to_string(),
-                "Failed to open /etc/rukinia/rukinia.conf".to_string(),
+                "rukinia init settings".to_string(),
+                "Failed to load settings".to_string(),
                 e.to_string(),
             )
-            .display_result();
-            return;
-        }
+        })
+}
+
+/// Makes the `[tasks]` settings available to the tasks, see `rukinia_tasks_settings`.
+///
+/// Only the first call has an effect.
+pub fn rukinia_set_tasks_settings(settings: TasksConfig) {
+    let _ = TASKS_CONFIG.set(settings);
+}
+
+/// Returns the `[tasks]` settings, the defaults if none were set.
+pub fn rukinia_tasks_settings() -> &'static TasksConfig {
+    TASKS_CONFIG.get_or_init(TasksConfig::default)
+}
+
+pub fn rukinia_use_settings(settings: &RukiniaConfig) -> Result<Runtime, Box<dyn Error>> {
+    let mut builder = match settings.tokio.flavor {
+        RuntimeFlavor::CurrentThread => tokio::runtime::Builder::new_current_thread(),
+        RuntimeFlavor::MultiThread => tokio::runtime::Builder::new_multi_thread(),
     };
+    if let (RuntimeFlavor::MultiThread, Some(worker_threads)) =
+        (settings.tokio.flavor, settings.tokio.worker_threads)
+    {
+        builder.worker_threads(worker_threads);
+    }
+
+    let runtime = builder.enable_all().build()?;
+
+    Ok(runtime)
 }
 
-pub fn rukinia_read_test_file(file: &mut File, buffer: &mut String) {
-    if let Err(e) = file.read_to_string(buffer) {
+/// Test plan read when no path is given
+pub const DEFAULT_TEST_PLAN: &str = "/etc/rukinia/rukinia.conf";
+
+/// Directory of the task plugins searched when none is given in the settings
+pub const DEFAULT_PLUGIN_PATH: &str = "/usr/lib/rukinia/tasks";
+
+/// Path standing for the standard input
+pub const STDIN_TEST_PLAN: &str = "-";
+
+/// Extension of the test plans read from a directory
+const TEST_PLAN_EXTENSION: &str = "conf";
+
+/// The content of a test plan and the path it was read from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RukiniaTestPlan {
+    pub path: String,
+    pub contents: String,
+    /// The test expressions of the plan and of the plans it includes, see
+    /// `rukinia_read_plan_lines`.
+    pub lines: Vec<RukiniaPlanLine>,
+}
+
+impl RukiniaTestPlan {
+    /// Creates the test plan read from `path`, reading the plans it includes.
+    ///
+    /// # Errors
+    /// Returns a `RukiniaError` if an included plan cannot be read.
+    pub fn new(path: &str, contents: &str) -> Result<Self, RukiniaError> {
+        Ok(RukiniaTestPlan {
+            path: path.to_string(),
+            contents: contents.to_string(),
+            lines: rukinia_read_plan_lines(path, contents)?,
+        })
+    }
+}
+
+pub fn rukinia_open_test_file(path: &str) -> Result<File, RukiniaError> {
+    File::open(path).map_err(|e| {
         RukiniaError::new(
-            "rukinia init read /etc/rukinia/rukinia.conf".to_string(),
-            "Failed to read /etc/rukinia/rukinia.conf".to_string(),
+            format!("rukinia init open {}", path),
+            format!("Failed to open {}", path),
             e.to_string(),
         )
-        .display_result();
+    })
+}
+
+pub fn rukinia_read_test_file(
+    file: &mut impl Read,
+    path: &str,
+    buffer: &mut String,
+) -> Result<(), RukiniaError> {
+    file.read_to_string(buffer).map(|_| ()).map_err(|e| {
+        RukiniaError::new(
+            format!("rukinia init read {}", path),
+            format!("Failed to read {}", path),
+            e.to_string(),
+        )
+    })
+}
+
+/// Expands the test plan paths given by the user into the list of plans to read.
+///
+/// A directory is replaced by its `*.conf` files, in lexical order, and `-`
+/// stands for the standard input. `DEFAULT_TEST_PLAN` is used when `paths` is empty.
+///
+/// # Errors
+/// Returns a `RukiniaError` naming the path that could not be inspected.
+pub fn rukinia_expand_test_plan_paths(paths: &[String]) -> Result<Vec<String>, RukiniaError> {
+    if paths.is_empty() {
+        return Ok(vec![DEFAULT_TEST_PLAN.to_string()]);
+    }
+
+    let mut expanded = Vec::new();
+    for path in paths {
+        if path != STDIN_TEST_PLAN && Path::new(path).is_dir() {
+            expanded.extend(rukinia_list_test_plan_directory(path)?);
+        } else {
+            expanded.push(path.clone());
+        }
+    }
+
+    Ok(expanded)
+}
+
+fn rukinia_list_test_plan_directory(path: &str) -> Result<Vec<String>, RukiniaError> {
+    let directory_error = |e: std::io::Error| {
+        RukiniaError::new(
+            format!("rukinia init list {}", path),
+            format!("Failed to list {}", path),
+            e.to_string(),
+        )
+    };
+
+    let mut plans = Vec::new();
+    for entry in fs::read_dir(path).map_err(directory_error)? {
+        let entry_path = entry.map_err(directory_error)?.path();
+        if entry_path.is_file() && entry_path.extension() == Some(OsStr::new(TEST_PLAN_EXTENSION)) {
+            plans.push(entry_path.to_string_lossy().into_owned());
+        }
+    }
+    plans.sort();
+
+    Ok(plans)
+}
+
+/// Reads every test plan found at `paths`, see `rukinia_expand_test_plan_paths`,
+/// with the plans they include.
+///
+/// # Errors
+/// Returns a `RukiniaError` naming the first path that could not be read, or
+/// locating the first `include` that failed.
+pub fn rukinia_load_test_plans(paths: &[String]) -> Result<Vec<RukiniaTestPlan>, RukiniaError> {
+    let mut plans = Vec::new();
+
+    for path in rukinia_expand_test_plan_paths(paths)? {
+        let mut contents = String::new();
+        if path == STDIN_TEST_PLAN {
+            rukinia_read_test_file(&mut io::stdin(), "stdin", &mut contents)?;
+        } else {
+            let mut file = rukinia_open_test_file(&path)?;
+            rukinia_read_test_file(&mut file, &path, &mut contents)?;
+        }
+        plans.push(RukiniaTestPlan::new(&path, &contents)?);
     }
+
+    Ok(plans)
 }
@@ -0,0 +1,221 @@
+//! Rukinia Test Expression Lexer
+//!
+//! This module splits a single test expression into tokens that can be consumed
+//! by the parser in `core::parser`.
+//!
+//! # Overview
+//! The lexer follows shell-like quoting rules:
+//! - Unquoted words are separated by whitespace and by `(` / `)`.
+//! - Double quotes group characters, `\"` and `\\` can be used inside them.
+//! - Single quotes group characters literally, without any escape.
+//! - A backslash outside of quotes escapes the next character.
+//!
+//! Adjacent quoted and unquoted parts are joined into a single argument, so
+//! `--name="eth 0"` becomes the argument `--name=eth 0`. Any token containing a
+//! quoted part is never interpreted as a keyword or a parenthesis.
+
+use std::fmt;
+
+use crate::core::rukinia_result::RukiniaError;
+
+/// Kind of token produced by the lexer.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TokenKind {
+    /// A bare word that may be a keyword (`not`, `and`, ...) or a task name.
+    Word(String),
+    /// A word that contained at least one quoted part.
+    Quoted(String),
+    /// An unquoted `(`.
+    LeftParen,
+    /// An unquoted `)`.
+    RightParen,
+}
+
+/// A token and the column (1-based, in characters) where it starts.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub column: usize,
+}
+
+impl Token {
+    /// Returns the text of a word or quoted token, `None` for parentheses.
+    pub fn get_text(&self) -> Option<&str> {
+        match &self.kind {
+            TokenKind::Word(text) | TokenKind::Quoted(text) => Some(text.as_str()),
+            TokenKind::LeftParen | TokenKind::RightParen => None,
+        }
+    }
+
+    /// Returns true if the token is the unquoted keyword `keyword`.
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.kind, TokenKind::Word(word) if word == keyword)
+    }
+}
+
+/// A problem found in a test expression.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExpressionError {
+    /// Column (1-based, in characters) where the problem was found.
+    pub column: usize,
+    /// Description of the problem, without its column.
+    pub message: String,
+    /// What was expected instead, if it helps fixing the problem.
+    pub hint: Option<String>,
+}
+
+impl ExpressionError {
+    pub fn new(column: usize, message: impl Into<String>) -> Self {
+        ExpressionError {
+            column,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    pub fn with_hint(self, hint: String) -> Self {
+        ExpressionError {
+            hint: Some(hint),
+            ..self
+        }
+    }
+
+    /// Converts the problem into the error raised when running the test `expression`.
+    pub fn to_rukinia_error(&self, expression: &str, description: &str) -> RukiniaError {
+        RukiniaError::new(
+            expression.to_string(),
+            description.to_string(),
+            self.to_string(),
+        )
+    }
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.column)?;
+        if let Some(hint) = &self.hint {
+            write!(f, ", {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits a test expression into tokens.
+///
+/// # Arguments
+/// * `expression` - A single test expression.
+///
+/// # Errors
+/// Returns a `RukiniaError` if a quote is left open or if the expression ends
+/// with an escaping backslash.
+pub fn tokenize(expression: &str) -> Result<Vec<Token>, RukiniaError> {
+    tokenize_expression(expression)
+        .map_err(|e| e.to_rukinia_error(expression, "Failed to tokenize rukinia test"))
+}
+
+/// Splits a test expression into tokens, see `tokenize`.
+pub(crate) fn tokenize_expression(expression: &str) -> Result<Vec<Token>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().enumerate().peekable();
+
+    while let Some(&(index, ch)) = chars.peek() {
+        let column = index + 1;
+
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        if ch == '(' || ch == ')' {
+            chars.next();
+            tokens.push(Token {
+                kind: if ch == '(' {
+                    TokenKind::LeftParen
+                } else {
+                    TokenKind::RightParen
+                },
+                column,
+            });
+            continue;
+        }
+
+        let mut word = String::new();
+        let mut quoted = false;
+
+        while let Some(&(index, ch)) = chars.peek() {
+            if ch.is_whitespace() || ch == '(' || ch == ')' {
+                break;
+            }
+            chars.next();
+            match ch {
+                '"' => {
+                    quoted = true;
+                    loop {
+                        match chars.next() {
+                            Some((_, '"')) => break,
+                            Some((_, '\\')) => match chars.next() {
+                                Some((_, escaped @ ('"' | '\\'))) => word.push(escaped),
+                                Some((_, other)) => {
+                                    word.push('\\');
+                                    word.push(other);
+                                }
+                                None => {
+                                    return Err(ExpressionError::new(
+                                        index + 1,
+                                        "Unterminated double quote",
+                                    ))
+                                }
+                            },
+                            Some((_, other)) => word.push(other),
+                            None => {
+                                return Err(ExpressionError::new(
+                                    index + 1,
+                                    "Unterminated double quote",
+                                ))
+                            }
+                        }
+                    }
+                }
+                '\'' => {
+                    quoted = true;
+                    loop {
+                        match chars.next() {
+                            Some((_, '\'')) => break,
+                            Some((_, other)) => word.push(other),
+                            None => {
+                                return Err(ExpressionError::new(
+                                    index + 1,
+                                    "Unterminated single quote",
+                                ))
+                            }
+                        }
+                    }
+                }
+                '\\' => {
+                    quoted = true;
+                    match chars.next() {
+                        Some((_, escaped)) => word.push(escaped),
+                        None => {
+                            return Err(ExpressionError::new(
+                                index + 1,
+                                "Dangling escape character",
+                            ))
+                        }
+                    }
+                }
+                other => word.push(other),
+            }
+        }
+
+        tokens.push(Token {
+            kind: if quoted {
+                TokenKind::Quoted(word)
+            } else {
+                TokenKind::Word(word)
+            },
+            column,
+        });
+    }
+
+    Ok(tokens)
+}
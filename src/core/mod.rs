@@ -1,7 +1,12 @@
 pub mod configuration;
 pub mod interactive_shell;
+pub mod lexer;
 pub mod operator;
+pub mod parser;
+pub mod plan;
+pub mod reporter;
 pub mod rukinia_result;
 pub mod run_tasks;
 pub mod save_test_result;
 pub mod syntax;
+pub mod variables;
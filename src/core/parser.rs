@@ -0,0 +1,467 @@
+//! Rukinia Test Expression Parser
+//!
+//! This module turns the tokens produced by `core::lexer` into an expression tree
+//! that `core::run_tasks` walks to execute a test.
+//!
+//! # Grammar
+//! ```text
+//! test       := condition* expression
+//! condition  := ("skip_if" | "only_if") "(" expression ")"
+//! expression := term (("and" | "or") term)*
+//! term       := modifier* (task | "(" expression ")")
+//! modifier   := "not" | "as" ARGUMENT | "id" ARGUMENT
+//! task       := TASK_NAME ARGUMENT*
+//! ```
+//!
+//! `and` and `or` share the same precedence and are evaluated from left to right.
+//! Task arguments run until the next unquoted `and`, `or`, `)` or the end of the
+//! expression; quote an argument to use one of these words literally.
+//!
+//! The conditions prefixing a test are evaluated first: `skip_if` skips the test when
+//! its condition passes, `only_if` when it does not. A condition cannot have an `id`.
+
+use std::str::FromStr;
+
+use crate::core::lexer::{tokenize_expression, ExpressionError, Token, TokenKind};
+use crate::core::operator::LogicalOperator;
+use crate::core::rukinia_result::RukiniaError;
+use crate::core::syntax::{ConditionKind, Syntax, SyntaxForTrait};
+use crate::core::variables::RukiniaVariables;
+use crate::tasks::task::RukiniaAllTasks;
+
+/// Node of a parsed test expression.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    /// A single task with its arguments and the modifiers prefixing it.
+    Task {
+        task: RukiniaAllTasks,
+        arguments: Vec<String>,
+        syntax: SyntaxForTrait,
+    },
+    /// A parenthesized expression with the modifiers prefixing it.
+    Group {
+        expression: Box<Expression>,
+        syntax: SyntaxForTrait,
+    },
+    /// Two expressions combined with `and` / `or`.
+    Logical {
+        operator: LogicalOperator,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    /// A test prefixed with `skip_if` / `only_if`, run or skipped according to its condition.
+    Conditional {
+        kind: ConditionKind,
+        condition: Box<Expression>,
+        expression: Box<Expression>,
+    },
+}
+
+impl Expression {
+    /// Returns the `as` label applied to the whole expression, if any.
+    pub fn get_as(&self) -> Option<String> {
+        match self {
+            Expression::Task { syntax, .. } | Expression::Group { syntax, .. } => syntax.get_as(),
+            Expression::Logical { .. } => None,
+            Expression::Conditional { expression, .. } => expression.get_as(),
+        }
+    }
+
+    /// Returns the first `id` found in the expression, from left to right.
+    pub fn get_id(&self) -> Option<String> {
+        self.get_ids().into_iter().next()
+    }
+
+    /// Returns every task of the expression with its arguments, from left to right.
+    pub fn get_tasks(&self) -> Vec<(&RukiniaAllTasks, &[String])> {
+        match self {
+            Expression::Task {
+                task, arguments, ..
+            } => vec![(task, arguments.as_slice())],
+            Expression::Group { expression, .. } => expression.get_tasks(),
+            Expression::Logical { left, right, .. }
+            | Expression::Conditional {
+                condition: left,
+                expression: right,
+                ..
+            } => {
+                let mut tasks = left.get_tasks();
+                tasks.extend(right.get_tasks());
+                tasks
+            }
+        }
+    }
+
+    /// Returns the expression with the variables of the task arguments and `as` labels
+    /// replaced by their values, see `RukiniaVariables::expand`.
+    ///
+    /// # Errors
+    /// Returns a description of the first problem found, e.g. an undefined variable.
+    pub fn expand(&self, variables: &RukiniaVariables) -> Result<Expression, String> {
+        match self {
+            Expression::Task {
+                task,
+                arguments,
+                syntax,
+            } => Ok(Expression::Task {
+                task: task.clone(),
+                arguments: arguments
+                    .iter()
+                    .map(|argument| variables.expand(argument))
+                    .collect::<Result<_, _>>()?,
+                syntax: expand_syntax(syntax, variables)?,
+            }),
+            Expression::Group { expression, syntax } => Ok(Expression::Group {
+                expression: Box::new(expression.expand(variables)?),
+                syntax: expand_syntax(syntax, variables)?,
+            }),
+            Expression::Logical {
+                operator,
+                left,
+                right,
+            } => Ok(Expression::Logical {
+                operator: *operator,
+                left: Box::new(left.expand(variables)?),
+                right: Box::new(right.expand(variables)?),
+            }),
+            Expression::Conditional {
+                kind,
+                condition,
+                expression,
+            } => Ok(Expression::Conditional {
+                kind: *kind,
+                condition: Box::new(condition.expand(variables)?),
+                expression: Box::new(expression.expand(variables)?),
+            }),
+        }
+    }
+
+    /// Returns every `id` found in the expression, from left to right.
+    pub fn get_ids(&self) -> Vec<String> {
+        match self {
+            Expression::Task { syntax, .. } => syntax_ids(syntax),
+            Expression::Group { expression, syntax } => {
+                let mut ids = syntax_ids(syntax);
+                ids.extend(expression.get_ids());
+                ids
+            }
+            Expression::Logical { left, right, .. }
+            | Expression::Conditional {
+                condition: left,
+                expression: right,
+                ..
+            } => {
+                let mut ids = left.get_ids();
+                ids.extend(right.get_ids());
+                ids
+            }
+        }
+    }
+}
+
+/// Parses a single test expression into an `Expression` tree.
+///
+/// # Arguments
+/// * `expression` - The test expression, e.g. `not (rukinia_user root) or rukinia_kmod i2c_hid`.
+///
+/// # Errors
+/// Returns a `RukiniaError` describing the first problem found and its column.
+///
+/// # Example
+/// ```rust,ignore
+/// let tree = parse_expression("as \"root is here\" rukinia_user root")?;
+/// ```
+pub fn parse_expression(expression: &str) -> Result<Expression, RukiniaError> {
+    match parse_expression_with_columns(expression) {
+        Ok((tree, _)) => Ok(tree),
+        Err(e) => Err(e.to_rukinia_error(expression, "Failed to parse rukinia test")),
+    }
+}
+
+/// Columns (1-based, in characters) of the parts of a parsed test expression.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct ExpressionColumns {
+    /// Column of every task name, from left to right.
+    pub tasks: Vec<usize>,
+    /// Column of every `id` argument, from left to right.
+    pub ids: Vec<usize>,
+}
+
+/// Parses a single test expression, keeping track of where its parts are.
+///
+/// The tasks and ids of the tree are found in the same order when walking it from
+/// left to right, see `Expression::get_ids`.
+///
+/// # Errors
+/// Returns an `ExpressionError` locating the first problem found.
+///
+/// # Example
+/// ```rust,ignore
+/// let (tree, columns) = parse_expression_with_columns("id root rukinia_user root")?;
+/// assert_eq!(columns.tasks, vec![9]);
+/// ```
+pub fn parse_expression_with_columns(
+    expression: &str,
+) -> Result<(Expression, ExpressionColumns), ExpressionError> {
+    let tokens = tokenize_expression(expression)?;
+    let mut parser = Parser {
+        expression,
+        tokens,
+        position: 0,
+        columns: ExpressionColumns::default(),
+    };
+
+    let tree = parser.parse_test()?;
+    match parser.peek() {
+        None => Ok((tree, parser.columns)),
+        Some(token) => Err(ExpressionError::new(
+            token.column,
+            format!("Unexpected {}", describe_token(token)),
+        )),
+    }
+}
+
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+    columns: ExpressionColumns,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn end_column(&self) -> usize {
+        self.expression.chars().count() + 1
+    }
+
+    fn peek_operator(&self) -> Option<LogicalOperator> {
+        match self.peek().map(|token| &token.kind) {
+            Some(TokenKind::Word(word)) => LogicalOperator::from_str(word).ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_test(&mut self) -> Result<Expression, ExpressionError> {
+        let kind = match self.peek() {
+            Some(token) if token.is_keyword(ConditionKind::SKIP_IF) => ConditionKind::SkipIf,
+            Some(token) if token.is_keyword(ConditionKind::ONLY_IF) => ConditionKind::OnlyIf,
+            _ => return self.parse_expression(),
+        };
+        self.next();
+
+        let ids = self.columns.ids.len();
+        let condition = match self.peek() {
+            Some(Token {
+                kind: TokenKind::LeftParen,
+                ..
+            }) => self.parse_term()?,
+            Some(token) => {
+                return Err(ExpressionError::new(
+                    token.column,
+                    format!("Unexpected {}", describe_token(token)),
+                )
+                .with_hint(format!("expected {} (<condition>) <test>", kind)))
+            }
+            None => {
+                return Err(ExpressionError::new(
+                    self.end_column(),
+                    format!("Missing condition for '{}'", kind),
+                ))
+            }
+        };
+        if let Some(column) = self.columns.ids.get(ids) {
+            return Err(ExpressionError::new(
+                *column,
+                format!("A condition of '{}' cannot have an id", kind),
+            ));
+        }
+
+        Ok(Expression::Conditional {
+            kind,
+            condition: Box::new(condition),
+            expression: Box::new(self.parse_test()?),
+        })
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, ExpressionError> {
+        let mut left = self.parse_term()?;
+
+        while let Some(operator) = self.peek_operator() {
+            self.next();
+            let right = self.parse_term()?;
+            left = Expression::Logical {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn parse_term(&mut self) -> Result<Expression, ExpressionError> {
+        let syntax = self.parse_syntax()?;
+
+        let token = match self.next() {
+            Some(token) => token,
+            None => return Err(ExpressionError::new(self.end_column(), "No task in test")),
+        };
+
+        match &token.kind {
+            TokenKind::LeftParen => {
+                let expression = self.parse_expression()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RightParen,
+                        ..
+                    }) => Ok(Expression::Group {
+                        expression: Box::new(expression),
+                        syntax,
+                    }),
+                    Some(other) => Err(ExpressionError::new(
+                        other.column,
+                        format!("Unexpected {}", describe_token(&other)),
+                    )
+                    .with_hint(format!(
+                        "expected ')' to close '(' at column {}",
+                        token.column
+                    ))),
+                    None => Err(ExpressionError::new(
+                        token.column,
+                        "Missing ')' to close '('",
+                    )),
+                }
+            }
+            TokenKind::Word(name) => match RukiniaAllTasks::from_str(name) {
+                Ok(task) => {
+                    self.columns.tasks.push(token.column);
+                    Ok(Expression::Task {
+                        task,
+                        arguments: self.parse_arguments(),
+                        syntax,
+                    })
+                }
+                Err(_) => Err(ExpressionError::new(
+                    token.column,
+                    format!("Unknown task '{}'", name),
+                )),
+            },
+            _ => Err(ExpressionError::new(
+                token.column,
+                format!("Unexpected {}", describe_token(&token)),
+            )
+            .with_hint("expected a task".to_string())),
+        }
+    }
+
+    fn parse_syntax(&mut self) -> Result<SyntaxForTrait, ExpressionError> {
+        let mut syntax = Vec::new();
+
+        while let Some(token) = self.peek() {
+            if token.is_keyword(Syntax::NOT) {
+                self.next();
+                syntax.push(Syntax::Not);
+            } else if token.is_keyword(Syntax::AS) {
+                let keyword = self.next().unwrap();
+                syntax.push(Syntax::As(self.parse_syntax_argument(&keyword)?));
+            } else if token.is_keyword(Syntax::ID) {
+                let keyword = self.next().unwrap();
+                if let Some(token) = self.peek() {
+                    self.columns.ids.push(token.column);
+                }
+                syntax.push(Syntax::Id(self.parse_syntax_argument(&keyword)?));
+            } else {
+                break;
+            }
+        }
+
+        Ok(SyntaxForTrait::Arguments(syntax))
+    }
+
+    fn parse_syntax_argument(&mut self, keyword: &Token) -> Result<String, ExpressionError> {
+        match self.next() {
+            Some(token) => match token.get_text() {
+                Some(text) => Ok(text.to_string()),
+                None => Err(ExpressionError::new(
+                    token.column,
+                    format!(
+                        "Missing argument for '{}'",
+                        keyword.get_text().unwrap_or_default()
+                    ),
+                )),
+            },
+            None => Err(ExpressionError::new(
+                self.end_column(),
+                format!(
+                    "Missing argument for '{}'",
+                    keyword.get_text().unwrap_or_default()
+                ),
+            )),
+        }
+    }
+
+    fn parse_arguments(&mut self) -> Vec<String> {
+        let mut arguments = Vec::new();
+
+        while self.peek_operator().is_none() {
+            match self.peek().and_then(|token| token.get_text()) {
+                Some(text) => {
+                    arguments.push(text.to_string());
+                    self.next();
+                }
+                None => break,
+            }
+        }
+
+        arguments
+    }
+}
+
+/// Expands the variables of the `as` labels of `syntax`, the ids being kept as written.
+fn expand_syntax(
+    syntax: &SyntaxForTrait,
+    variables: &RukiniaVariables,
+) -> Result<SyntaxForTrait, String> {
+    match syntax {
+        SyntaxForTrait::Arguments(vector) => Ok(SyntaxForTrait::Arguments(
+            vector
+                .iter()
+                .map(|s| match s {
+                    Syntax::As(label) => variables.expand(label).map(Syntax::As),
+                    other => Ok(other.clone()),
+                })
+                .collect::<Result<_, _>>()?,
+        )),
+    }
+}
+
+fn syntax_ids(syntax: &SyntaxForTrait) -> Vec<String> {
+    match syntax {
+        SyntaxForTrait::Arguments(vector) => vector
+            .iter()
+            .filter_map(|s| match s {
+                Syntax::Id(id) => Some(id.clone()),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+fn describe_token(token: &Token) -> String {
+    match &token.kind {
+        TokenKind::LeftParen => "'('".to_string(),
+        TokenKind::RightParen => "')'".to_string(),
+        TokenKind::Word(text) | TokenKind::Quoted(text) => format!("'{}'", text),
+    }
+}
@@ -0,0 +1,559 @@
+//! Rukinia Test Plan Reader
+//!
+//! This module splits the contents of a test plan into the test expressions parsed
+//! by `core::parser`, one per logical line, and reads the test plans it includes.
+//!
+//! # Overview
+//! - A `#` starting a word outside of quotes starts a comment running to the end of
+//!   the line, so `rukinia_user root # admin` only keeps `rukinia_user root`, while
+//!   `rukinia_http_request http://host/#top` and `"#"` are left untouched.
+//! - Lines holding nothing but whitespace or a comment are skipped.
+//! - A line ending with an unescaped backslash continues on the next line, the
+//!   backslash being replaced by a space.
+//! - `include <path>` is replaced by the tests of another test plan. The path is
+//!   relative to the including plan (to the current directory for stdin) and may be
+//!   a glob, its matches being included in lexical order.
+//! - `set NAME value` sets a variable for the next lines, see `core::variables`.
+//! - `if <test>` ... `else` ... `end` runs the lines of a block according to the result
+//!   of a test, which is not reported. Every `if` is closed by an `end` in its own plan.
+//!
+//! ```text
+//! include common/users.conf
+//! include soc/*.conf
+//! set IFACE eth0
+//!
+//! # Both network interfaces are up
+//! rukinia_netif_is_up ${IFACE} \
+//!     and rukinia_netif_is_up eth1
+//!
+//! if rukinia_kmod wl18xx
+//!     rukinia_netif_is_up wlan0
+//! else
+//!     not rukinia_netif_is_up wlan0
+//! end
+//! ```
+//!
+//! Every logical line keeps the path of its test plan and the number of the line
+//! where it starts.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::configuration::{rukinia_open_test_file, rukinia_read_test_file, STDIN_TEST_PLAN};
+use crate::core::lexer::{tokenize_expression, ExpressionError, Token, TokenKind};
+use crate::core::parser::{parse_expression_with_columns, Expression, ExpressionColumns};
+use crate::core::rukinia_result::RukiniaError;
+use crate::core::variables::{is_variable_name, SET_KEYWORD};
+
+/// Keyword of the statement including another test plan.
+pub const INCLUDE_KEYWORD: &str = "include";
+
+/// Keyword opening a block run if its test passes.
+pub const IF_KEYWORD: &str = "if";
+
+/// Keyword starting the lines of a block run if its test does not pass.
+pub const ELSE_KEYWORD: &str = "else";
+
+/// Keyword closing a block.
+pub const END_KEYWORD: &str = "end";
+
+/// A test expression of a test plan, see `rukinia_plan_lines`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RukiniaPlanLine {
+    /// Path of the test plan holding the expression, an included plan if any.
+    pub path: String,
+    /// Line (1-based) where the expression starts in the test plan.
+    pub line: usize,
+    /// The expression, without its comment and with its continuation lines joined.
+    pub text: String,
+}
+
+impl RukiniaPlanLine {
+    /// Returns where the expression starts, as `path:line`.
+    pub fn get_location(&self) -> String {
+        format!("{}:{}", self.path, self.line)
+    }
+
+    /// Returns the keyword of the line if it opens, splits or closes a block.
+    pub fn get_block_keyword(&self) -> Option<&'static str> {
+        let tokens = tokenize_expression(&self.text).ok()?;
+        let first = tokens.first()?;
+        [IF_KEYWORD, ELSE_KEYWORD, END_KEYWORD]
+            .into_iter()
+            .find(|keyword| first.is_keyword(keyword))
+    }
+
+    /// Parses the statement of the line.
+    ///
+    /// # Errors
+    /// Returns an `ExpressionError` locating the first problem found.
+    pub fn parse(&self) -> Result<RukiniaPlanStatement, ExpressionError> {
+        let tokens = tokenize_expression(&self.text)?;
+        match tokens.first() {
+            Some(token) if token.is_keyword(SET_KEYWORD) => {}
+            Some(token) if token.is_keyword(IF_KEYWORD) => return self.parse_if(&tokens),
+            Some(token) if token.is_keyword(ELSE_KEYWORD) || token.is_keyword(END_KEYWORD) => {
+                return match tokens.get(1) {
+                    Some(extra) => Err(ExpressionError::new(
+                        extra.column,
+                        format!("Unexpected {}", describe_name(extra)),
+                    )),
+                    None if token.is_keyword(ELSE_KEYWORD) => Ok(RukiniaPlanStatement::Else),
+                    None => Ok(RukiniaPlanStatement::End),
+                };
+            }
+            _ => {
+                let (expression, columns) = parse_expression_with_columns(&self.text)?;
+                return Ok(RukiniaPlanStatement::Test(expression, columns));
+            }
+        }
+
+        let end_column = self.text.chars().count() + 1;
+        match &tokens[1..] {
+            [name, value] if value.get_text().is_some() => match &name.kind {
+                TokenKind::Word(name) if is_variable_name(name) => Ok(RukiniaPlanStatement::Set {
+                    name: name.clone(),
+                    value: value.get_text().unwrap_or_default().to_string(),
+                }),
+                _ => Err(ExpressionError::new(
+                    name.column,
+                    format!("Invalid variable name {}", describe_name(name)),
+                )),
+            },
+            [_, _, extra, ..] => Err(ExpressionError::new(
+                extra.column,
+                format!("Unexpected {}", describe_name(extra)),
+            )
+            .with_hint("quote the value to use several words".to_string())),
+            arguments => Err(ExpressionError::new(
+                arguments
+                    .iter()
+                    .find(|token| token.get_text().is_none())
+                    .map_or(end_column, |token| token.column),
+                format!("Missing value for '{}'", SET_KEYWORD),
+            )
+            .with_hint("expected set NAME <value>".to_string())),
+        }
+    }
+
+    /// Parses the test of an `if`, its columns being those of the whole line.
+    fn parse_if(&self, tokens: &[Token]) -> Result<RukiniaPlanStatement, ExpressionError> {
+        let start = match tokens.get(1) {
+            Some(token) => token.column,
+            None => {
+                return Err(ExpressionError::new(
+                    self.text.chars().count() + 1,
+                    format!("Missing test for '{}'", IF_KEYWORD),
+                )
+                .with_hint("expected if <test>".to_string()))
+            }
+        };
+        let shift = |column: usize| column + start - 1;
+
+        let condition: String = self.text.chars().skip(start - 1).collect();
+        let (expression, mut columns) =
+            parse_expression_with_columns(&condition).map_err(|mut e| {
+                e.column = shift(e.column);
+                e
+            })?;
+        columns.tasks = columns.tasks.into_iter().map(shift).collect();
+        columns.ids = columns.ids.into_iter().map(shift).collect();
+
+        match columns.ids.first() {
+            Some(&column) => Err(ExpressionError::new(
+                column,
+                format!("The test of '{}' cannot have an id", IF_KEYWORD),
+            )),
+            None => Ok(RukiniaPlanStatement::If(expression, columns)),
+        }
+    }
+}
+
+/// A statement of a test plan, see `RukiniaPlanLine::parse`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum RukiniaPlanStatement {
+    /// A test, with the columns of its parts.
+    Test(Expression, ExpressionColumns),
+    /// `set NAME value`, setting a variable for the next lines of the test plan.
+    Set { name: String, value: String },
+    /// `if <test>`, opening a block run if the test passes, with the columns of its parts.
+    If(Expression, ExpressionColumns),
+    /// `else`, starting the lines of the block run if the test of its `if` does not pass.
+    Else,
+    /// `end`, closing the block.
+    End,
+}
+
+fn describe_name(token: &Token) -> String {
+    match token.get_text() {
+        Some(text) => format!("'{}'", text),
+        None => "parenthesis".to_string(),
+    }
+}
+
+/// Splits the contents of a test plan into its test expressions, without reading
+/// the plans it includes.
+///
+/// # Arguments
+/// * `path` - Path of the test plan, recorded in every line.
+/// * `contents` - The test plan, as read from its file.
+///
+/// # Returns
+/// The non-empty logical lines of the plan, comments removed, in the order of the plan.
+///
+/// # Example
+/// ```rust,ignore
+/// let lines = rukinia_plan_lines("a.conf", "# Users\nrukinia_user root \\\n  and rukinia_group root\n");
+/// assert_eq!(lines[0].line, 2);
+/// assert_eq!(lines[0].text, "rukinia_user root and rukinia_group root");
+/// ```
+pub fn rukinia_plan_lines(path: &str, contents: &str) -> Vec<RukiniaPlanLine> {
+    let mut lines = Vec::new();
+    let mut current: Option<RukiniaPlanLine> = None;
+
+    for (index, raw_line) in contents.lines().enumerate() {
+        let text = strip_comment(raw_line).trim_end();
+        let (text, continued) = match strip_continuation(text) {
+            Some(text) => (text.trim_end(), true),
+            None => (text, false),
+        };
+
+        let line = match current.take() {
+            Some(mut line) => {
+                let text = text.trim_start();
+                if !text.is_empty() {
+                    line.text.push(' ');
+                    line.text.push_str(text);
+                }
+                line
+            }
+            None => RukiniaPlanLine {
+                path: path.to_string(),
+                line: index + 1,
+                text: text.to_string(),
+            },
+        };
+
+        if continued {
+            current = Some(line);
+        } else if !line.text.trim().is_empty() {
+            lines.push(line);
+        }
+    }
+
+    // A continuation on the last line of the plan ends with it
+    if let Some(line) = current {
+        if !line.text.trim().is_empty() {
+            lines.push(line);
+        }
+    }
+
+    lines
+}
+
+/// Splits the contents of a test plan into its test expressions, replacing every
+/// `include` by the expressions of the plans it names.
+///
+/// # Arguments
+/// * `path` - Path of the test plan, `-` for stdin, the base of its relative includes.
+/// * `contents` - The test plan, as read from its file.
+///
+/// # Errors
+/// Returns a `RukiniaError` locating the `include` if a plan cannot be read, if a
+/// glob matches no plan, or if a plan includes itself, directly or not. The blocks are
+/// checked by `rukinia_find_block_problems`.
+///
+/// # Example
+/// ```rust,ignore
+/// let lines = rukinia_read_plan_lines("boards/imx8.conf", "include ../common/*.conf\n")?;
+/// assert_eq!(lines[0].path, "boards/../common/users.conf");
+/// ```
+pub fn rukinia_read_plan_lines(
+    path: &str,
+    contents: &str,
+) -> Result<Vec<RukiniaPlanLine>, RukiniaError> {
+    let mut including = Vec::new();
+    if path != STDIN_TEST_PLAN {
+        including.push((canonical_path(Path::new(path)), path.to_string()));
+    }
+    let mut lines = Vec::new();
+    read_plan_lines(path, contents, &mut including, &mut lines)?;
+    Ok(lines)
+}
+
+/// Checks that every `if` is closed by an `end` of its own plan, with at most one `else`.
+///
+/// Lines that cannot be parsed are not reported here, see `RukiniaPlanLine::parse`.
+///
+/// # Arguments
+/// * `lines` - The lines of a test plan, as read by `rukinia_read_plan_lines`.
+///
+/// # Returns
+/// Every problem found with the index of its line in `lines`, in the order of the lines.
+pub fn rukinia_find_block_problems(lines: &[RukiniaPlanLine]) -> Vec<(usize, ExpressionError)> {
+    let mut problems = Vec::new();
+    // Problems are located at the keyword of their line
+    let problem = |index: usize, message: String| {
+        let indent = lines[index]
+            .text
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+        (index, ExpressionError::new(indent + 1, message))
+    };
+    let unclosed = |index: usize| {
+        problem(
+            index,
+            format!("Missing '{}' to close the '{}'", END_KEYWORD, IF_KEYWORD),
+        )
+    };
+    // The index of the `if` of every open block, and whether its `else` was found
+    let mut blocks: Vec<(usize, bool)> = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let keyword = match line.get_block_keyword() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        if keyword == IF_KEYWORD {
+            blocks.push((index, false));
+            continue;
+        }
+
+        // Back in a plan with an open block, those of the plans it included are not closed
+        if blocks
+            .iter()
+            .any(|&(open, _)| lines[open].path == line.path)
+        {
+            while let Some(&(open, _)) = blocks.last() {
+                if lines[open].path == line.path {
+                    break;
+                }
+                problems.push(unclosed(open));
+                blocks.pop();
+            }
+        }
+
+        let message = match blocks
+            .last_mut()
+            .filter(|(open, _)| lines[*open].path == line.path)
+        {
+            None => format!("'{}' without '{}'", keyword, IF_KEYWORD),
+            Some((open, true)) if keyword == ELSE_KEYWORD => format!(
+                "Several '{}' for the '{}' at {}",
+                ELSE_KEYWORD,
+                IF_KEYWORD,
+                lines[*open].get_location()
+            ),
+            Some((_, found_else)) if keyword == ELSE_KEYWORD => {
+                *found_else = true;
+                continue;
+            }
+            Some(_) => {
+                blocks.pop();
+                continue;
+            }
+        };
+        problems.push(problem(index, message));
+    }
+
+    problems.extend(blocks.into_iter().map(|(open, _)| unclosed(open)));
+    problems.sort_by_key(|&(index, _)| index);
+    problems
+}
+
+/// Checks the blocks of a test plan before running it, see `rukinia_find_block_problems`.
+///
+/// # Errors
+/// Returns a `RukiniaError` locating the first problem found.
+pub fn rukinia_check_blocks(lines: &[RukiniaPlanLine]) -> Result<(), RukiniaError> {
+    match rukinia_find_block_problems(lines).into_iter().next() {
+        Some((index, error)) => Err(RukiniaError::new(
+            lines[index].text.clone(),
+            "Invalid block".to_string(),
+            format!("{}: {}", lines[index].get_location(), error.message),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Appends the expressions of a test plan to `lines`, reading its includes.
+///
+/// `including` holds the plans being read, each with the location including the next
+/// one, to detect cycles.
+fn read_plan_lines(
+    path: &str,
+    contents: &str,
+    including: &mut Vec<(PathBuf, String)>,
+    lines: &mut Vec<RukiniaPlanLine>,
+) -> Result<(), RukiniaError> {
+    for line in rukinia_plan_lines(path, contents) {
+        let target = match include_target(&line)? {
+            Some(target) => target,
+            None => {
+                lines.push(line);
+                continue;
+            }
+        };
+
+        for included in include_paths(path, &line, &target)? {
+            let canonical = canonical_path(Path::new(&included));
+            if let Some(position) = including.iter().position(|(path, _)| *path == canonical) {
+                let mut chain: Vec<String> = including[position + 1..]
+                    .iter()
+                    .map(|(_, location)| location.clone())
+                    .collect();
+                chain.push(line.get_location());
+                chain.push(included.clone());
+                return Err(RukiniaError::new(
+                    format!("{} {}", INCLUDE_KEYWORD, target),
+                    "Include cycle".to_string(),
+                    chain.join(" -> "),
+                ));
+            }
+
+            let mut included_contents = String::new();
+            rukinia_open_test_file(&included)
+                .and_then(|mut file| {
+                    rukinia_read_test_file(&mut file, &included, &mut included_contents)
+                })
+                .map_err(|e| {
+                    include_error(
+                        &line,
+                        &target,
+                        e.system_error.unwrap_or_default(),
+                        e.system_error_message,
+                    )
+                })?;
+
+            including.push((canonical, line.get_location()));
+            read_plan_lines(&included, &included_contents, including, lines)?;
+            including.pop();
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the path of an `include` statement, `None` if the line is a test.
+fn include_target(line: &RukiniaPlanLine) -> Result<Option<String>, RukiniaError> {
+    let tokens = match tokenize_expression(&line.text) {
+        Ok(tokens) => tokens,
+        // Reported as a test that cannot be parsed
+        Err(_) => return Ok(None),
+    };
+    if !tokens
+        .first()
+        .is_some_and(|token| token.is_keyword(INCLUDE_KEYWORD))
+    {
+        return Ok(None);
+    }
+
+    match &tokens[1..] {
+        [token] if token.get_text().is_some() => Ok(token.get_text().map(str::to_string)),
+        arguments => Err(RukiniaError::new(
+            line.text.clone(),
+            "Invalid include".to_string(),
+            format!(
+                "{}: Expected <path>, got {} argument(s)",
+                line.get_location(),
+                arguments.len()
+            ),
+        )),
+    }
+}
+
+/// Returns the test plans named by an `include`, relative to the including plan.
+fn include_paths(
+    path: &str,
+    line: &RukiniaPlanLine,
+    target: &str,
+) -> Result<Vec<String>, RukiniaError> {
+    let base = if path == STDIN_TEST_PLAN {
+        Path::new("")
+    } else {
+        Path::new(path).parent().unwrap_or(Path::new(""))
+    };
+    let included = base.join(target).to_string_lossy().into_owned();
+    if !target.contains(['*', '?', '[']) {
+        return Ok(vec![included]);
+    }
+
+    let matches = glob::glob(&included)
+        .map_err(|e| include_error(line, target, "Invalid include".to_string(), e.to_string()))?;
+    let mut paths: Vec<String> = matches
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        return Err(include_error(
+            line,
+            target,
+            "Invalid include".to_string(),
+            format!("No test plan matches {}", included),
+        ));
+    }
+    Ok(paths)
+}
+
+/// Error raised by an `include`, its message starting with the location of the `include`.
+fn include_error(
+    line: &RukiniaPlanLine,
+    target: &str,
+    system_error: String,
+    message: String,
+) -> RukiniaError {
+    RukiniaError::new(
+        format!("{} {}", INCLUDE_KEYWORD, target),
+        system_error,
+        format!("{}: {}", line.get_location(), message),
+    )
+}
+
+/// Returns the absolute path of a test plan, the path itself if it cannot be resolved.
+fn canonical_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Removes the comment of a line, following the quoting rules of `core::lexer`.
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut word_start = true;
+
+    for (index, ch) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if let Some(open) = quote {
+            if ch == open {
+                quote = None;
+            } else if ch == '\\' && open == '"' {
+                escaped = true;
+            }
+        } else if ch == '#' && word_start {
+            return &line[..index];
+        } else if ch == '"' || ch == '\'' {
+            quote = Some(ch);
+        } else if ch == '\\' {
+            escaped = true;
+        }
+        word_start = quote.is_none() && (ch.is_whitespace() || ch == '(' || ch == ')');
+    }
+
+    line
+}
+
+/// Returns the line without its trailing backslash if it continues on the next line.
+///
+/// An even number of trailing backslashes are escaped backslashes, not a continuation.
+fn strip_continuation(line: &str) -> Option<&str> {
+    let backslashes = line.chars().rev().take_while(|&ch| ch == '\\').count();
+    if backslashes % 2 == 1 {
+        Some(&line[..line.len() - 1])
+    } else {
+        None
+    }
+}
@@ -0,0 +1,558 @@
+//! Rukinia Reporters
+//!
+//! A `Reporter` receives the events of a test run as they happen: the console
+//! printers and every report format implement it, and `rukinia_execute_all_tests`
+//! feeds all of them from the same execution.
+//!
+//! Library users can register their own sink by implementing `Reporter`:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use rukinia::core::configuration::RukiniaTestPlan;
+//! use rukinia::core::reporter::{rukinia_reporters, Reporter, ReporterResult};
+//! use rukinia::core::rukinia_result::{RukiniaError, RukiniaResultEntry};
+//! use rukinia::core::run_tasks::{rukinia_execute_all_tests, TestSelection};
+//! use rukinia::core::save_test_result::ConsoleOutput;
+//! use rukinia::core::variables::RukiniaVariables;
+//!
+//! struct Counter(usize);
+//!
+//! impl Reporter for Counter {
+//!     fn name(&self) -> String {
+//!         "counter".to_string()
+//!     }
+//!
+//!     fn on_test_result(&mut self, _entry: &RukiniaResultEntry) -> ReporterResult {
+//!         self.0 += 1;
+//!         Ok(())
+//!     }
+//!
+//!     fn on_error(&mut self, _error: &RukiniaError, _duration: Duration) -> ReporterResult {
+//!         self.0 += 1;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! # async fn run() -> Result<(), RukiniaError> {
+//! let plans = vec![RukiniaTestPlan::new("board.conf", "rukinia_user root\n")?];
+//! let mut reporters = rukinia_reporters(ConsoleOutput::Text, &[]);
+//! reporters.push(Box::new(Counter(0)));
+//! let outcome = rukinia_execute_all_tests(
+//!     &plans,
+//!     &mut reporters,
+//!     &TestSelection::default(),
+//!     &RukiniaVariables::default(),
+//! )
+//! .await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Stdout, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::core::configuration::{RukiniaTestPlan, STDIN_TEST_PLAN};
+use crate::core::parser::Expression;
+use crate::core::rukinia_result::{
+    RukiniaError, RukiniaResultEntry, RukiniaRunInfo, RukiniaRunOutcome,
+};
+use crate::core::save_test_result::{
+    lava_sanitize, ConsoleOutput, FormatOutput, JUnitReport, JsonReport, LavaResult, LavaWriter,
+    ReportMode, ResultFormat, TapWriter,
+};
+
+/// Number of slowest tests listed in the summary of the text console.
+pub const SLOWEST_TESTS_DISPLAYED: usize = 5;
+
+/// Result of a `Reporter` event: an error means the reporter failed to write it.
+pub type ReporterResult = Result<(), Box<dyn Error>>;
+
+/// A test about to run, as given to `Reporter::on_test_start`.
+pub struct RukiniaTestStart<'a> {
+    /// Path of the test plan holding the test.
+    pub plan: &'a str,
+    /// Line of the test in its plan, starting at 1.
+    pub line: usize,
+    /// The test expression, as written in the plan.
+    pub test: &'a str,
+    /// The parsed test, `None` if it cannot be parsed.
+    pub expression: Option<&'a Expression>,
+}
+
+impl RukiniaTestStart<'_> {
+    /// Returns the `id` of the test, if any.
+    pub fn get_id(&self) -> Option<String> {
+        self.expression.and_then(|expression| expression.get_id())
+    }
+
+    /// Returns the `as` label of the test, if any.
+    pub fn get_as(&self) -> Option<String> {
+        self.expression.and_then(|expression| expression.get_as())
+    }
+}
+
+/// Sink receiving the events of a test run.
+///
+/// Events are sent in this order: `on_run_start`, then for each test plan
+/// `on_plan_start`, `on_test_start` and `on_test_result` or `on_error` for
+/// each test, `on_plan_end`, and finally `on_run_end`. Only the results of the
+/// tests are mandatory, the other events do nothing by default.
+///
+/// A failing event is reported on the console and does not stop the run.
+pub trait Reporter: Send {
+    /// Name of the reporter, shown when it fails to write an event.
+    fn name(&self) -> String;
+
+    /// Called once, before the first test plan, with the run ID, start time and
+    /// host that reports should record.
+    fn on_run_start(&mut self, _run: &RukiniaRunInfo) -> ReporterResult {
+        Ok(())
+    }
+
+    /// Called before the tests of a test plan.
+    fn on_plan_start(&mut self, _plan: &RukiniaTestPlan) -> ReporterResult {
+        Ok(())
+    }
+
+    /// Called before a test runs.
+    fn on_test_start(&mut self, _test: &RukiniaTestStart) -> ReporterResult {
+        Ok(())
+    }
+
+    /// Called when a test produced a result.
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult;
+
+    /// Called when a test raised a `RukiniaError` after running for `duration`.
+    fn on_error(&mut self, error: &RukiniaError, duration: Duration) -> ReporterResult;
+
+    /// Called after the tests of a test plan.
+    fn on_plan_end(&mut self, _plan: &RukiniaTestPlan) -> ReporterResult {
+        Ok(())
+    }
+
+    /// Called once, after the last test plan, with the outcome of the whole run.
+    fn on_run_end(&mut self, _outcome: &RukiniaRunOutcome) -> ReporterResult {
+        Ok(())
+    }
+}
+
+/// Creates the reporters of a run: the console printer, then one reporter per report.
+///
+/// # Arguments
+/// * `console_output` - The format of the results printed on stdout.
+/// * `result_formats` - The reports to write.
+pub fn rukinia_reporters(
+    console_output: ConsoleOutput,
+    result_formats: &[ResultFormat],
+) -> Vec<Box<dyn Reporter>> {
+    let mut reporters = vec![rukinia_console_reporter(console_output)];
+    reporters.extend(result_formats.iter().map(rukinia_report_reporter));
+    reporters
+}
+
+/// Creates the reporter printing the results on stdout in `console_output` format.
+pub fn rukinia_console_reporter(console_output: ConsoleOutput) -> Box<dyn Reporter> {
+    match console_output {
+        ConsoleOutput::Text => Box::new(TextConsoleReporter::new(SLOWEST_TESTS_DISPLAYED)),
+        ConsoleOutput::Tap => Box::new(TapConsoleReporter::new()),
+        ConsoleOutput::Lava => Box::new(LavaConsoleReporter::new()),
+    }
+}
+
+/// Creates the reporter writing the report described by `format`.
+pub fn rukinia_report_reporter(format: &ResultFormat) -> Box<dyn Reporter> {
+    let file = ReportFile::new(format);
+    match format.format {
+        FormatOutput::Csv => Box::new(CsvReporter { file }),
+        FormatOutput::TextFile => Box::new(TextFileReporter { file }),
+        FormatOutput::JUnit => Box::new(JUnitReporter {
+            file,
+            report: JUnitReport::new(&format.options.suite_name),
+        }),
+        FormatOutput::Json => Box::new(JsonReporter {
+            file,
+            report: JsonReport::new(format.options.pretty),
+        }),
+        FormatOutput::JsonLines => Box::new(JsonLinesReporter { file }),
+        FormatOutput::Tap => Box::new(TapFileReporter {
+            file,
+            tap: TapWriter::default(),
+        }),
+    }
+}
+
+/// Prints coloured `[PASS]`/`[FAIL]`/`[ERROR]` lines, then a summary of the run.
+pub struct TextConsoleReporter {
+    slowest: usize,
+}
+
+impl TextConsoleReporter {
+    /// Creates a printer listing the `slowest` slowest tests in its summary.
+    pub fn new(slowest: usize) -> Self {
+        TextConsoleReporter { slowest }
+    }
+}
+
+impl Reporter for TextConsoleReporter {
+    fn name(&self) -> String {
+        "text console".to_string()
+    }
+
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+        entry.display_result();
+        Ok(())
+    }
+
+    fn on_error(&mut self, error: &RukiniaError, _duration: Duration) -> ReporterResult {
+        error.display_result();
+        Ok(())
+    }
+
+    fn on_run_end(&mut self, outcome: &RukiniaRunOutcome) -> ReporterResult {
+        outcome.display_summary(self.slowest);
+        Ok(())
+    }
+}
+
+/// Prints a TAP stream on stdout, closed by its plan line at the end of the run.
+pub struct TapConsoleReporter {
+    tap: TapWriter<Stdout>,
+}
+
+impl TapConsoleReporter {
+    pub fn new() -> Self {
+        TapConsoleReporter {
+            tap: TapWriter::new(io::stdout()),
+        }
+    }
+}
+
+impl Default for TapConsoleReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reporter for TapConsoleReporter {
+    fn name(&self) -> String {
+        "TAP console".to_string()
+    }
+
+    fn on_run_start(&mut self, run: &RukiniaRunInfo) -> ReporterResult {
+        Ok(self.tap.add_run(run)?)
+    }
+
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+        Ok(self.tap.add_result(entry)?)
+    }
+
+    fn on_error(&mut self, error: &RukiniaError, duration: Duration) -> ReporterResult {
+        Ok(self.tap.add_error(error, duration)?)
+    }
+
+    fn on_run_end(&mut self, _outcome: &RukiniaRunOutcome) -> ReporterResult {
+        Ok(self.tap.finish()?)
+    }
+}
+
+/// Prints LAVA test case signals on stdout, one test set per test plan.
+pub struct LavaConsoleReporter {
+    lava: LavaWriter<Stdout>,
+    test_case_id: String,
+}
+
+impl LavaConsoleReporter {
+    pub fn new() -> Self {
+        LavaConsoleReporter {
+            lava: LavaWriter::new(io::stdout()),
+            test_case_id: String::new(),
+        }
+    }
+}
+
+impl Default for LavaConsoleReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reporter for LavaConsoleReporter {
+    fn name(&self) -> String {
+        "LAVA console".to_string()
+    }
+
+    fn on_plan_start(&mut self, plan: &RukiniaTestPlan) -> ReporterResult {
+        Ok(self.lava.start_test_set(&lava_test_set_name(&plan.path))?)
+    }
+
+    fn on_test_start(&mut self, test: &RukiniaTestStart) -> ReporterResult {
+        self.test_case_id = self.lava.test_case_id(&lava_test_case_name(test));
+        Ok(self.lava.start_test_case(&self.test_case_id)?)
+    }
+
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+        Ok(self.lava.end_test_case(
+            &self.test_case_id,
+            LavaResult::from(&entry.result_type),
+            entry.duration,
+        )?)
+    }
+
+    fn on_error(&mut self, _error: &RukiniaError, duration: Duration) -> ReporterResult {
+        Ok(self
+            .lava
+            .end_test_case(&self.test_case_id, LavaResult::Unknown, duration)?)
+    }
+
+    fn on_plan_end(&mut self, _plan: &RukiniaTestPlan) -> ReporterResult {
+        Ok(self.lava.stop_test_set()?)
+    }
+}
+
+/// Returns the name of the LAVA test set wrapping the tests of a plan: its file name
+/// without extension.
+fn lava_test_set_name(path: &str) -> String {
+    if path == STDIN_TEST_PLAN {
+        return "stdin".to_string();
+    }
+    let name = Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    lava_sanitize(&name)
+}
+
+/// Returns the name used to build the LAVA test case ID of a test.
+///
+/// The ID is sent before the test runs, so it comes from the `id` or `as`
+/// modifier of the test when present, from the test expression otherwise.
+fn lava_test_case_name(test: &RukiniaTestStart) -> String {
+    test.get_id()
+        .or_else(|| test.get_as())
+        .unwrap_or_else(|| test.test.trim().to_string())
+}
+
+/// File written by a report.
+struct ReportFile {
+    /// Path the report is configured with.
+    path: String,
+    mode: ReportMode,
+    /// The current run and the path of the file it writes, see `ReportMode::get_path`.
+    run: RukiniaRunInfo,
+    run_path: String,
+}
+
+impl ReportFile {
+    fn new(format: &ResultFormat) -> Self {
+        let run = RukiniaRunInfo::now();
+        ReportFile {
+            path: format.path.clone(),
+            mode: format.options.mode,
+            run_path: format.options.mode.get_path(&format.path, &run),
+            run,
+        }
+    }
+
+    /// Starts writing `run`: the file is emptied unless the report appends to it.
+    fn start(&mut self, run: &RukiniaRunInfo) -> io::Result<()> {
+        self.run = run.clone();
+        self.run_path = self.mode.get_path(&self.path, run);
+        self.open().map(|_| ())
+    }
+
+    /// Opens the file of the run, at its end if the report appends to it.
+    fn open(&self) -> io::Result<File> {
+        let append = self.mode == ReportMode::Append;
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(&self.run_path)
+    }
+
+    /// Replaces the content of the file of the run, or appends to it.
+    fn write_all(&self, contents: &[u8]) -> ReporterResult {
+        let mut file = BufWriter::new(self.open()?);
+        file.write_all(contents)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Appends a row per test to a CSV file, with the columns of the run.
+pub struct CsvReporter {
+    file: ReportFile,
+}
+
+impl Reporter for CsvReporter {
+    fn name(&self) -> String {
+        format!("CSV report {}", self.file.path)
+    }
+
+    fn on_run_start(&mut self, run: &RukiniaRunInfo) -> ReporterResult {
+        Ok(self.file.start(run)?)
+    }
+
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+        entry.write_csv(&self.file.run_path, &self.file.run)
+    }
+
+    fn on_error(&mut self, error: &RukiniaError, duration: Duration) -> ReporterResult {
+        error.write_csv(&self.file.run_path, duration, &self.file.run)
+    }
+}
+
+/// Appends a line per test to a text file, after a line describing the run.
+pub struct TextFileReporter {
+    file: ReportFile,
+}
+
+impl Reporter for TextFileReporter {
+    fn name(&self) -> String {
+        format!("text report {}", self.file.path)
+    }
+
+    fn on_run_start(&mut self, run: &RukiniaRunInfo) -> ReporterResult {
+        self.file.start(run)?;
+        self.file.write_all(
+            format!(
+                "# Run {} started {} on {}, rukinia {}\n",
+                run.run_id,
+                run.get_start_time(),
+                run.hostname,
+                run.version
+            )
+            .as_bytes(),
+        )
+    }
+
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+        entry.write_text(&self.file.run_path)
+    }
+
+    fn on_error(&mut self, error: &RukiniaError, duration: Duration) -> ReporterResult {
+        error.write_text(&self.file.run_path, duration)
+    }
+}
+
+/// Appends a JSON object per test and its run to a JSON Lines file.
+pub struct JsonLinesReporter {
+    file: ReportFile,
+}
+
+impl Reporter for JsonLinesReporter {
+    fn name(&self) -> String {
+        format!("JSON Lines report {}", self.file.path)
+    }
+
+    fn on_run_start(&mut self, run: &RukiniaRunInfo) -> ReporterResult {
+        Ok(self.file.start(run)?)
+    }
+
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+        entry.write_json_line(&self.file.run_path, &self.file.run)
+    }
+
+    fn on_error(&mut self, error: &RukiniaError, duration: Duration) -> ReporterResult {
+        error.write_json_line(&self.file.run_path, duration, &self.file.run)
+    }
+}
+
+/// Collects the tests of the run and writes a JUnit XML report at its end.
+pub struct JUnitReporter {
+    file: ReportFile,
+    report: JUnitReport,
+}
+
+impl Reporter for JUnitReporter {
+    fn name(&self) -> String {
+        format!("JUnit report {}", self.file.path)
+    }
+
+    fn on_run_start(&mut self, run: &RukiniaRunInfo) -> ReporterResult {
+        self.report.set_run(run);
+        Ok(self.file.start(run)?)
+    }
+
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+        self.report.add_result(entry);
+        Ok(())
+    }
+
+    fn on_error(&mut self, error: &RukiniaError, duration: Duration) -> ReporterResult {
+        self.report.add_error(error, duration);
+        Ok(())
+    }
+
+    fn on_run_end(&mut self, _outcome: &RukiniaRunOutcome) -> ReporterResult {
+        self.file.write_all(self.report.to_xml().as_bytes())
+    }
+}
+
+/// Collects the tests of the run and writes a JSON report at its end.
+pub struct JsonReporter {
+    file: ReportFile,
+    report: JsonReport,
+}
+
+impl Reporter for JsonReporter {
+    fn name(&self) -> String {
+        format!("JSON report {}", self.file.path)
+    }
+
+    fn on_run_start(&mut self, run: &RukiniaRunInfo) -> ReporterResult {
+        self.report.set_run(run);
+        Ok(self.file.start(run)?)
+    }
+
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+        self.report.add_result(entry);
+        Ok(())
+    }
+
+    fn on_error(&mut self, error: &RukiniaError, duration: Duration) -> ReporterResult {
+        self.report.add_error(error, duration);
+        Ok(())
+    }
+
+    fn on_run_end(&mut self, _outcome: &RukiniaRunOutcome) -> ReporterResult {
+        self.file
+            .write_all(format!("{}\n", self.report.to_json()?).as_bytes())
+    }
+}
+
+/// Collects a TAP stream and writes it to a file at the end of the run.
+pub struct TapFileReporter {
+    file: ReportFile,
+    tap: TapWriter<Vec<u8>>,
+}
+
+impl Reporter for TapFileReporter {
+    fn name(&self) -> String {
+        format!("TAP report {}", self.file.path)
+    }
+
+    fn on_run_start(&mut self, run: &RukiniaRunInfo) -> ReporterResult {
+        self.file.start(run)?;
+        Ok(self.tap.add_run(run)?)
+    }
+
+    fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+        Ok(self.tap.add_result(entry)?)
+    }
+
+    fn on_error(&mut self, error: &RukiniaError, duration: Duration) -> ReporterResult {
+        Ok(self.tap.add_error(error, duration)?)
+    }
+
+    fn on_run_end(&mut self, _outcome: &RukiniaRunOutcome) -> ReporterResult {
+        let mut tap = std::mem::take(&mut self.tap);
+        tap.finish()?;
+        self.file.write_all(&tap.into_inner())
+    }
+}
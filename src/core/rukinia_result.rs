@@ -1,61 +1,237 @@
-use crate::core::save_test_result::CsvTestResult;
+use crate::core::save_test_result::{CsvTestResult, JsonTestResult};
+use crate::core::syntax::SyntaxForTrait;
+use std::collections::BTreeMap;
+use std::env;
 use std::error::Error;
 use std::fmt;
-use std::fs::OpenOptions;
-use std::io::{self, Write};
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Enables or disables the colours of the console output, see `ColorMode::is_enabled`.
+pub fn set_color_enabled(enabled: bool) {
+    COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `code` if colours are enabled, an empty string otherwise.
+fn color(code: &'static str) -> &'static str {
+    if COLOR_ENABLED.load(Ordering::Relaxed) {
+        code
+    } else {
+        ""
+    }
+}
+
+/// When the console output is coloured.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Colour the output when stdout is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Returns true if the console output must be coloured.
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            ColorMode::Auto => io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+impl fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorMode::Auto => write!(f, "auto"),
+            ColorMode::Always => write!(f, "always"),
+            ColorMode::Never => write!(f, "never"),
+        }
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(()),
+        }
+    }
+}
 
 #[derive(PartialEq, Clone)]
 pub enum RukiniaResultType {
     TestFail,
     TestSuccess,
+    /// The test was not run, see `skip_if` and `only_if` in `core::parser`.
+    TestSkip,
+}
+
+impl fmt::Display for RukiniaResultType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RukiniaResultType::TestSuccess => write!(f, "SUCCESS"),
+            RukiniaResultType::TestFail => write!(f, "FAIL"),
+            RukiniaResultType::TestSkip => write!(f, "SKIP"),
+        }
+    }
+}
+
+/// A task executed as part of a test, with the modifiers prefixing it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RukiniaTaskCall {
+    pub task: String,
+    pub arguments: Vec<String>,
+    pub syntax: SyntaxForTrait,
 }
 
 #[derive(Clone)]
 pub struct RukiniaResultEntry {
     pub label: String,
     pub result_type: RukiniaResultType,
+    pub duration: Duration,
+    /// Tasks executed to produce this result, in the order of the test expression.
+    pub tasks: Vec<RukiniaTaskCall>,
+    /// Modifiers applied to the whole test.
+    pub syntax: SyntaxForTrait,
+    /// Identifier given to the test with the `id` modifier.
+    pub id: Option<String>,
+    /// Where the test is written, as `path:line`, the included plan if any.
+    pub location: Option<String>,
+    /// Details reported by the tasks, e.g. by a plugin, written in the reports.
+    pub details: Option<String>,
+    /// Measures reported by the tasks, by name, written in the reports.
+    pub metrics: BTreeMap<String, f64>,
 }
 
 impl RukiniaResultEntry {
     pub fn new(result_type: RukiniaResultType, label: String) -> Self {
-        RukiniaResultEntry { label, result_type }
+        RukiniaResultEntry {
+            label,
+            result_type,
+            duration: Duration::ZERO,
+            tasks: Vec::new(),
+            syntax: SyntaxForTrait::Arguments(Vec::new()),
+            id: None,
+            location: None,
+            details: None,
+            metrics: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the details and metrics of another result, e.g. of the right side of `and`.
+    pub fn merge_details(&mut self, other: &RukiniaResultEntry) {
+        self.details = match (self.details.take(), &other.details) {
+            (Some(details), Some(other)) => Some(format!("{}\n{}", details, other)),
+            (details, other) => details.or_else(|| other.clone()),
+        };
+        self.metrics.extend(other.metrics.clone());
+    }
+
+    /// Returns the label prefixed with the test ID, if any, as printed in text outputs.
+    pub fn get_display_label(&self) -> String {
+        display_label(self.id.as_deref(), &self.label)
     }
 
     pub fn display_result(&self) {
-        const GREEN: &str = "\x1b[32m";
-        const RED: &str = "\x1b[31m";
-        const BOLD: &str = "\x1b[1m";
-        const RESET: &str = "\x1b[0m";
+        let (green, red, yellow, bold, reset) = (
+            color(GREEN),
+            color(RED),
+            color(YELLOW),
+            color(BOLD),
+            color(RESET),
+        );
         match self.result_type {
             RukiniaResultType::TestSuccess => {
-                println!("[{}{}PASS{}] : {}", GREEN, BOLD, RESET, self.label);
+                println!(
+                    "[{}{}PASS{}] : {}",
+                    green,
+                    bold,
+                    reset,
+                    self.get_display_label()
+                );
             }
             RukiniaResultType::TestFail => {
-                println!("[{}{}FAIL{}] : {}", RED, BOLD, RESET, self.label);
+                println!(
+                    "[{}{}FAIL{}] : {}",
+                    red,
+                    bold,
+                    reset,
+                    self.get_display_label()
+                );
+            }
+            RukiniaResultType::TestSkip => {
+                println!(
+                    "[{}{}SKIP{}] : {}{}",
+                    yellow,
+                    bold,
+                    reset,
+                    self.get_display_label(),
+                    self.details
+                        .as_deref()
+                        .map(|reason| format!(" ({})", reason))
+                        .unwrap_or_default()
+                );
             }
         }
     }
 
-    pub async fn write_csv(&self, csv_path: &str) -> Result<(), Box<dyn Error>> {
+    pub fn write_csv(&self, csv_path: &str, run: &RukiniaRunInfo) -> Result<(), Box<dyn Error>> {
         let (label, result) = match self.result_type {
             RukiniaResultType::TestSuccess => (&self.label, "SUCCESS"),
             RukiniaResultType::TestFail => (&self.label, "FAIL"),
+            RukiniaResultType::TestSkip => (&self.label, "SKIP"),
         };
 
-        let csv_result = CsvTestResult { label, result };
+        let csv_result = CsvTestResult {
+            id: self.id.as_deref().unwrap_or_default(),
+            label,
+            result,
+            duration: format_duration(self.duration),
+            run_id: &run.run_id,
+            run_start: run.get_start_time(),
+            hostname: &run.hostname,
+            version: &run.version,
+        };
         csv_result
             .append_csv_result(csv_path)
-            .await
             .map_err(|e| Box::new(e) as Box<dyn Error>)
     }
 
-    pub async fn write_text(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+    pub fn write_text(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
         let (label, result) = match self.result_type {
             RukiniaResultType::TestSuccess => (&self.label, "SUCCESS"),
             RukiniaResultType::TestFail => (&self.label, "FAIL"),
+            RukiniaResultType::TestSkip => (&self.label, "SKIP"),
         };
 
-        let test_result = format!("{}, {}\n", label, result);
+        let test_result = format!(
+            "{}, {}, {}s\n",
+            display_label(self.id.as_deref(), label),
+            result,
+            format_duration(self.duration)
+        );
 
         let file = OpenOptions::new()
             .append(true)
@@ -68,29 +244,251 @@ impl RukiniaResultEntry {
         Ok(())
     }
 
-    pub async fn write_junit(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
-        let (label, result) = match self.result_type {
-            RukiniaResultType::TestSuccess => (&self.label, "SUCCESS"),
-            RukiniaResultType::TestFail => (&self.label, "FAIL"),
-        };
+    pub fn write_json_line(
+        &self,
+        file_path: &str,
+        run: &RukiniaRunInfo,
+    ) -> Result<(), Box<dyn Error>> {
+        JsonTestResult::from_result(self).append_json_line(file_path, run)
+    }
+}
 
-        let test_result = format!("<testcase name=\"{}\" result=\"{}\" />\n", label, result);
+/// Identification of a test run, recorded in every report so that runs
+/// appended to the same file can be told apart.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RukiniaRunInfo {
+    /// Unique identifier of the run.
+    pub run_id: String,
+    pub start_time: SystemTime,
+    /// Name of the host running the tests.
+    pub hostname: String,
+    /// Version of rukinia running the tests.
+    pub version: String,
+}
 
-        let file = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(file_path)?;
+impl RukiniaRunInfo {
+    /// Describes a run starting now on this host.
+    pub fn now() -> Self {
+        let start_time = SystemTime::now();
+        let since_epoch = start_time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        RukiniaRunInfo {
+            run_id: format!("{:x}-{:x}", since_epoch.as_micros(), std::process::id()),
+            start_time,
+            hostname: hostname(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
 
-        let mut file = io::BufWriter::new(file);
-        file.write_all(test_result.as_bytes())?;
+    /// Returns the start time of the run, see `format_timestamp`.
+    pub fn get_start_time(&self) -> String {
+        format_timestamp(self.start_time)
+    }
+}
 
-        Ok(())
+/// Returns the name of the host, `unknown` if it cannot be found.
+fn hostname() -> String {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .or_else(|_| fs::read_to_string("/etc/hostname"))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| env::var("HOSTNAME").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Formats a time as an RFC 3339 UTC timestamp, e.g. `2024-05-02T13:45:12Z`.
+pub fn format_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, day_seconds) = ((seconds / 86400) as i64, seconds % 86400);
+
+    // Civil date from the number of days since 1970-01-01 (Howard Hinnant's algorithm)
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        day_seconds / 3600,
+        day_seconds / 60 % 60,
+        day_seconds % 60
+    )
+}
+
+/// Label and execution time of a single test.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RukiniaTestTiming {
+    pub label: String,
+    pub duration: Duration,
+}
+
+/// Aggregated outcome of a test run.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct RukiniaRunOutcome {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub errors: usize,
+    pub duration: Duration,
+    pub timings: Vec<RukiniaTestTiming>,
+}
+
+impl RukiniaRunOutcome {
+    /// Counts a test that produced a result.
+    pub fn add_result(&mut self, entry: &RukiniaResultEntry) {
+        match entry.result_type {
+            RukiniaResultType::TestSuccess => self.passed += 1,
+            RukiniaResultType::TestFail => self.failed += 1,
+            RukiniaResultType::TestSkip => self.skipped += 1,
+        }
+        self.add_timing(entry.label.clone(), entry.duration);
+    }
+
+    /// Counts a test that raised a `RukiniaError`.
+    pub fn add_error(&mut self, error: &RukiniaError, duration: Duration) {
+        self.errors += 1;
+        self.add_timing(error.label.clone(), duration);
+    }
+
+    fn add_timing(&mut self, label: String, duration: Duration) {
+        self.duration += duration;
+        self.timings.push(RukiniaTestTiming { label, duration });
+    }
+
+    /// Number of tests, skipped ones included.
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.skipped + self.errors
+    }
+
+    /// The `count` slowest tests, slowest first.
+    pub fn slowest(&self, count: usize) -> Vec<&RukiniaTestTiming> {
+        let mut timings: Vec<&RukiniaTestTiming> = self.timings.iter().collect();
+        timings.sort_by_key(|timing| std::cmp::Reverse(timing.duration));
+        timings.truncate(count);
+        timings
+    }
+
+    /// Prints the counters of the run and its `slowest` slowest tests.
+    pub fn display_summary(&self, slowest: usize) {
+        let (bold, reset) = (color(BOLD), color(RESET));
+        println!("{}Summary{}", bold, reset);
+        println!("  Total    : {}", self.total());
+        println!("  Passed   : {}", self.passed);
+        println!("  Failed   : {}", self.failed);
+        println!("  Skipped  : {}", self.skipped);
+        println!("  Errors   : {}", self.errors);
+        println!("  Duration : {}s", format_duration(self.duration));
+
+        let slowest_tests = self.slowest(slowest);
+        if !slowest_tests.is_empty() {
+            println!("{}Slowest tests{}", bold, reset);
+            for timing in slowest_tests {
+                println!("  {}s : {}", format_duration(timing.duration), timing.label);
+            }
+        }
+    }
+
+    /// Exit code of the run according to the `fail_on` policy.
+    pub fn exit_code(&self, fail_on: FailOn) -> RukiniaExitCode {
+        match fail_on {
+            FailOn::Fail if self.errors > 0 => RukiniaExitCode::SystemError,
+            FailOn::Fail if self.failed > 0 => RukiniaExitCode::TestFailed,
+            FailOn::Error if self.errors > 0 => RukiniaExitCode::SystemError,
+            _ => RukiniaExitCode::Success,
+        }
+    }
+}
+
+/// Process exit codes of the rukinia binary.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RukiniaExitCode {
+    /// Every test passed.
+    Success = 0,
+    /// At least one test failed.
+    TestFailed = 1,
+    /// At least one test raised a system error.
+    SystemError = 2,
+    /// The configuration or the test plan could not be loaded.
+    ConfigurationError = 3,
+}
+
+impl From<RukiniaExitCode> for ExitCode {
+    fn from(code: RukiniaExitCode) -> Self {
+        ExitCode::from(code as u8)
+    }
+}
+
+/// Policy deciding which test outcomes make the run exit with a non-zero code.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FailOn {
+    /// Exit with a non-zero code on failed tests and on system errors.
+    Fail,
+    /// Exit with a non-zero code on system errors only.
+    Error,
+    /// Always exit with code 0 once the test plan was loaded.
+    Never,
+}
+
+impl fmt::Display for FailOn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                FailOn::Fail => "fail",
+                FailOn::Error => "error",
+                FailOn::Never => "never",
+            }
+        )
+    }
+}
+
+impl FromStr for FailOn {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fail" => Ok(FailOn::Fail),
+            "error" => Ok(FailOn::Error),
+            "never" => Ok(FailOn::Never),
+            _ => Err(()),
+        }
     }
 }
 
 #[derive(Debug)]
 pub struct RukiniaError {
     pub label: String,
+    pub test_command: String,
+    pub system_error: Option<String>,
+    pub system_error_message: String,
+    /// Test of a plan that raised the error, boxed as most errors have none.
+    pub origin: Option<Box<RukiniaErrorOrigin>>,
+}
+
+/// Test of a plan that raised a `RukiniaError`.
+#[derive(Debug, Default)]
+pub struct RukiniaErrorOrigin {
+    /// Identifier of the test, if it has one.
+    pub id: Option<String>,
+    /// Where the test is written, as `path:line`.
+    pub location: Option<String>,
 }
 
 impl fmt::Display for RukiniaError {
@@ -134,10 +532,14 @@ impl RukiniaError {
     ) -> Self {
         RukiniaError {
             label: RukiniaError::format_message(
-                test_command,
-                input_system_error,
-                input_system_error_message,
+                test_command.clone(),
+                input_system_error.clone(),
+                input_system_error_message.clone(),
             ),
+            test_command,
+            system_error: Some(input_system_error),
+            system_error_message: input_system_error_message,
+            origin: None,
         }
     }
 
@@ -147,32 +549,85 @@ impl RukiniaError {
     ) -> Self {
         RukiniaError {
             label: RukiniaError::format_message_short(
-                test_command,
-                input_system_error_message,
+                test_command.clone(),
+                input_system_error_message.clone(),
             ),
+            test_command,
+            system_error: None,
+            system_error_message: input_system_error_message,
+            origin: None,
         }
     }
 
+    /// Attaches the identifier of the test that raised the error.
+    pub fn with_id(mut self, id: Option<String>) -> Self {
+        self.origin.get_or_insert_with(Box::default).id = id;
+        self
+    }
+
+    /// Attaches the location of the test that raised the error, as `path:line`.
+    pub fn with_location(mut self, location: String) -> Self {
+        self.origin.get_or_insert_with(Box::default).location = Some(location);
+        self
+    }
+
+    /// Returns the identifier of the test that raised the error, if it has one.
+    pub fn get_id(&self) -> Option<&str> {
+        self.origin.as_ref()?.id.as_deref()
+    }
+
+    /// Returns where the test that raised the error is written, if known.
+    pub fn get_location(&self) -> Option<&str> {
+        self.origin.as_ref()?.location.as_deref()
+    }
+
+    /// Returns the label prefixed with the test ID, if any, as printed in text outputs.
+    pub fn get_display_label(&self) -> String {
+        display_label(self.get_id(), &self.label)
+    }
+
     pub fn display_result(&self) {
-        const RED: &str = "\x1b[31m";
-        const BOLD: &str = "\x1b[1m";
-        const RESET: &str = "\x1b[0m";
-        println!("[{}{}ERROR{}] : {}", RED, BOLD, RESET, self.label);
+        let (red, bold, reset) = (color(RED), color(BOLD), color(RESET));
+        println!(
+            "[{}{}ERROR{}] : {}",
+            red,
+            bold,
+            reset,
+            self.get_display_label()
+        );
     }
 
-    pub async fn write_csv(&self, csv_path: &str) -> Result<(), Box<dyn Error>> {
+    pub fn write_csv(
+        &self,
+        csv_path: &str,
+        duration: Duration,
+        run: &RukiniaRunInfo,
+    ) -> Result<(), Box<dyn Error>> {
         let (label, result) = (&self.label, "SYSTEM ERROR");
-        let csv_result = CsvTestResult { label, result };
+        let csv_result = CsvTestResult {
+            id: self.get_id().unwrap_or_default(),
+            label,
+            result,
+            duration: format_duration(duration),
+            run_id: &run.run_id,
+            run_start: run.get_start_time(),
+            hostname: &run.hostname,
+            version: &run.version,
+        };
         csv_result
             .append_csv_result(csv_path)
-            .await
             .map_err(|e| Box::new(e) as Box<dyn Error>)
     }
 
-    pub async fn write_text(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+    pub fn write_text(&self, file_path: &str, duration: Duration) -> Result<(), Box<dyn Error>> {
         let (label, result) = (&self.label, "SYSTEM ERROR");
 
-        let test_result = format!("{}, {}\n", label, result);
+        let test_result = format!(
+            "{}, {}, {}s\n",
+            display_label(self.get_id(), label),
+            result,
+            format_duration(duration)
+        );
 
         let file = OpenOptions::new()
             .append(true)
@@ -185,19 +640,25 @@ impl RukiniaError {
         Ok(())
     }
 
-    pub async fn write_junit(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
-        let (label, result) = (&self.label, "SYSTEM ERROR");
-
-        let test_result = format!("<testcase name=\"{}\" result=\"{}\" />\n", label, result);
-
-        let file = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(file_path)?;
-
-        let mut file = io::BufWriter::new(file);
-        file.write_all(test_result.as_bytes())?;
+    pub fn write_json_line(
+        &self,
+        file_path: &str,
+        duration: Duration,
+        run: &RukiniaRunInfo,
+    ) -> Result<(), Box<dyn Error>> {
+        JsonTestResult::from_error(self, duration).append_json_line(file_path, run)
+    }
+}
 
-        Ok(())
+/// Returns the label prefixed with the test ID, if any, as printed in text outputs.
+pub fn display_label(id: Option<&str>, label: &str) -> String {
+    match id {
+        Some(id) => format!("[{}] {}", id, label),
+        None => label.to_string(),
     }
 }
+
+/// Formats a duration as seconds with millisecond precision, as used in every report.
+pub fn format_duration(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
@@ -12,396 +12,927 @@
 ///
 /// # Example Usage
 /// ```rust,ignore
-/// // Run analysis with an optional output format (e.g., CSV, JUnit)
-/// rukinia_run_analysis(None).await;
+/// // Run analysis, printing the results on the console
+/// let mut reporters = rukinia_reporters(ConsoleOutput::Text, &[]);
+/// let variables = RukiniaVariables::default();
+/// let outcome =
+///     rukinia_run_analysis(&[], &mut reporters, &TestSelection::default(), &variables).await?;
 /// ```
 ///
 /// # Functions
-/// - `rukinia_run_analysis`: Reads a test file and executes all tests within it.
-/// - `rukinia_execute_all_tests`: Iterates over the test expressions in a buffer and executes each one.
-/// - `rukinia_execute_single_test`: Processes a single test expression, handling logical operators and parentheses.
-/// - `evaluate_logical_expression`: Evaluates an expression with logical operators like AND/OR.
-/// - `convert_config_line_to_vector_string`: Converts a configuration line into a vector of strings.
-/// - `find_outermost_parentheses`: Finds the outermost parentheses in an expression.
-/// - `read_expression_task`: Reads a task expression and returns the corresponding result and remaining parts.
-/// - `create_task`: Creates a task from the parts of an expression.
+/// - `rukinia_run_analysis`: Reads the test plans and executes all tests within them.
+/// - `rukinia_check_test_ids`: Validates the test IDs of the test plans.
+/// - `rukinia_check_result_formats`: Validates the reports requested for a run.
+/// - `rukinia_check_test_plans`: Finds every problem of the test plans without running them.
+/// - `rukinia_describe_test_plans`: Describes what each test of the test plans would check, without running it.
+/// - `rukinia_execute_all_tests`: Iterates over the test expressions of the test plans, executes each one and sends its result to the reporters.
+/// - `rukinia_execute_single_test`: Parses a single test expression and evaluates it.
+/// - `rukinia_plan_line_statement`: Parses a line of a test plan, with its variables expanded.
+/// - `evaluate_logical_expression`: Walks an expression tree, combining results with AND/OR.
 /// - `execute_task`: Executes a task with the given arguments and syntax.
-/// - `write_result`: Writes the result of a test to a file in a specified format.
-/// - `write_result_error`: Handles writing error results to a specified format.
-use regex::Regex;
-use std::fs::File;
-use std::str::FromStr;
-
+/// - `notify`: Sends an event to every reporter.
+use crate::core::parser::{parse_expression, Expression};
+use crate::core::plan::{
+    rukinia_check_blocks, rukinia_find_block_problems, RukiniaPlanLine, RukiniaPlanStatement,
+    ELSE_KEYWORD, END_KEYWORD, IF_KEYWORD,
+};
+use crate::core::reporter::{Reporter, ReporterResult, RukiniaTestStart};
 use crate::core::syntax::SyntaxForTrait;
+use crate::core::variables::RukiniaVariables;
 
-use crate::tasks::bool::rukinia_false::RukiniaFalse;
-use crate::tasks::bool::rukinia_true::RukiniaTrue;
 use crate::tasks::task::*;
 
-use crate::core::configuration::rukinia_open_test_file;
-use crate::core::configuration::rukinia_read_test_file;
+use crate::core::configuration::rukinia_load_test_plans;
+use crate::core::configuration::RukiniaTestPlan;
 
 use super::rukinia_result::RukiniaResultType;
 use super::save_test_result::FormatOutput;
-use crate::core::save_test_result::ResultFormat;
+use crate::core::save_test_result::{ReportMode, ResultFormat};
 
 use crate::core::operator::LogicalOperator;
-use crate::core::syntax::Syntax;
-use once_cell::sync::Lazy;
-use std::error::Error;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Instant;
 
+use crate::core::rukinia_result::display_label;
 use crate::core::rukinia_result::RukiniaError;
 use crate::core::rukinia_result::RukiniaResultEntry;
+use crate::core::rukinia_result::RukiniaRunInfo;
+use crate::core::rukinia_result::RukiniaRunOutcome;
+use crate::core::rukinia_result::RukiniaTaskCall;
+
+/// Tests selected by their `id`, e.g. from the command line.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct TestSelection {
+    /// Only run the tests with one of these IDs; every test is run when empty.
+    pub only: Vec<String>,
+    /// Never run the tests with one of these IDs.
+    pub exclude: Vec<String>,
+}
 
-static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#""([^"]*)"|(\S+)"#).unwrap());
+impl TestSelection {
+    /// Returns true if a test with the given ID must be run.
+    pub fn is_selected(&self, id: Option<&str>) -> bool {
+        match id {
+            Some(id) if self.exclude.iter().any(|excluded| excluded == id) => false,
+            Some(id) => self.only.is_empty() || self.only.iter().any(|only| only == id),
+            None => self.only.is_empty(),
+        }
+    }
+}
 
-/// Reads a test file and executes all tests within it.
+/// Reads the test plans and executes all tests within them.
 ///
-/// This function opens a test file, reads its contents, and processes the test
-/// expressions. It also handles formatting the output of each test based on
-/// the specified result format (e.g., CSV, Text, JUnit).
+/// This function reads the test plans, checks their test IDs, and processes the
+/// test expressions. Every result is sent to the reporters, which print it or
+/// write it to a report (e.g., CSV, Text, JUnit).
 ///
 /// # Arguments
-/// * `result_format` - Optional argument that specifies the format for the result output.
+/// * `paths` - The test plan files, directories or `-` for stdin, see `rukinia_load_test_plans`.
+/// * `reporters` - The sinks receiving the results, see `rukinia_reporters`.
+/// * `selection` - The tests to run, by `id`.
+/// * `variables` - The variables defined on the command line, see `RukiniaVariables`.
+///
+/// # Returns
+/// The aggregated outcome of the run.
+///
+/// # Errors
+/// Returns a `RukiniaError` if a test plan cannot be opened or read, if a block is not
+/// closed (see `rukinia_check_blocks`) or if their test IDs are invalid (see
+/// `rukinia_check_test_ids`).
 ///
 /// # Example
 /// ```rust,ignore
-/// rukinia_run_analysis(None).await;
+/// let paths = vec!["/etc/rukinia/rukinia.d".to_string()];
+/// let mut reporters = rukinia_reporters(ConsoleOutput::Text, &[]);
+/// let variables = RukiniaVariables::default();
+/// let outcome =
+///     rukinia_run_analysis(&paths, &mut reporters, &TestSelection::default(), &variables).await?;
 /// ```
-pub async fn rukinia_run_analysis(result_format: Option<ResultFormat>) {
-    let mut file: Option<File> = None;
-    let mut contents = String::new();
-
-    rukinia_open_test_file(&mut file);
-
-    let mut file = match file {
-        Some(f) => f,
-        None => return,
-    };
-
-    rukinia_read_test_file(&mut file, &mut contents);
-    drop(file);
+pub async fn rukinia_run_analysis(
+    paths: &[String],
+    reporters: &mut [Box<dyn Reporter>],
+    selection: &TestSelection,
+    variables: &RukiniaVariables,
+) -> Result<RukiniaRunOutcome, RukiniaError> {
+    let plans = rukinia_load_test_plans(paths)?;
+
+    for plan in &plans {
+        rukinia_check_blocks(&plan.lines)?;
+    }
+    rukinia_check_test_ids(&plans, selection)?;
 
-    rukinia_execute_all_tests(&contents, result_format).await;
+    Ok(rukinia_execute_all_tests(&plans, reporters, selection, variables).await)
 }
 
-/// Executes all tests found in the input buffer.
+/// Checks the test IDs of the test plans before running them.
 ///
-/// This function iterates through each line in the input buffer, executes
-/// each test expression, and displays the results. It also handles writing
-/// the results to the specified output format.
+/// The IDs of tests and of `if` conditions are checked, like in
+/// `rukinia_check_test_plans`. Lines that cannot be parsed are ignored here, they
+/// are reported when run.
 ///
 /// # Arguments
-/// * `buffer` - The string containing multiple test expressions, each on a new line.
-/// * `output_format` - Optional result format specifying how the output should be written (e.g., CSV, JUnit).
+/// * `plans` - The test plans, each holding one test expression per line.
+/// * `selection` - The tests selected by `id`, which must all exist in the plans.
 ///
-/// # Example
-/// ```rust,ignore
-/// rukinia_execute_all_tests("test1\n test2", None).await;
-/// ```
-///
-pub async fn rukinia_execute_all_tests(buffer: &str, output_format: Option<ResultFormat>) {
-    for line in buffer.lines() {
-        match rukinia_execute_single_test(line).await {
-            Ok(rukinia_entry) => {
-                rukinia_entry.display_result();
-                if let Some(ref valid_format) = output_format {
-                    if let Err(e) = write_result(&rukinia_entry, valid_format).await {
-                        RukiniaError::new(
-                            format!("Write {}", valid_format.format),
-                            "Failed to write output".to_string(),
-                            e.to_string(),
-                        )
-                        .display_result();
-                    }
-                }
+/// # Errors
+/// Returns a `RukiniaError` if a test has several IDs, if an ID is used by
+/// several tests, or if the selection refers to an unknown ID.
+pub fn rukinia_check_test_ids(
+    plans: &[RukiniaTestPlan],
+    selection: &TestSelection,
+) -> Result<(), RukiniaError> {
+    let mut lines_by_id: HashMap<String, String> = HashMap::new();
+
+    for plan in plans {
+        for plan_line in &plan.lines {
+            let line = plan_line.text.as_str();
+            let location = plan_line.get_location();
+            let ids = match plan_line.parse() {
+                Ok(RukiniaPlanStatement::Test(expression, _))
+                | Ok(RukiniaPlanStatement::If(expression, _)) => expression.get_ids(),
+                Ok(_) | Err(_) => continue,
+            };
+
+            if ids.len() > 1 {
+                return Err(RukiniaError::new(
+                    line.to_string(),
+                    "Invalid test id".to_string(),
+                    format!("Test at {} has several ids: {}", location, ids.join(", ")),
+                ));
             }
-            Err(rukinia_error) => {
-                rukinia_error.display_result();
-                if let Some(ref valid_format) = output_format {
-                    if let Err(e) = write_result_error(&rukinia_error, valid_format).await {
-                        RukiniaError::new(
-                            format!("Write {}", valid_format.format),
-                            "Failed to write error output".to_string(),
-                            e.to_string(),
-                        )
-                        .display_result();
-                    }
+
+            for id in ids {
+                if let Some(first_location) = lines_by_id.get(&id) {
+                    return Err(RukiniaError::new(
+                        line.to_string(),
+                        "Invalid test id".to_string(),
+                        format!(
+                            "Duplicate test id '{}' at {} and {}",
+                            id, first_location, location
+                        ),
+                    ));
                 }
+                lines_by_id.insert(id, location.clone());
             }
-        };
+        }
     }
+
+    for id in selection.only.iter().chain(selection.exclude.iter()) {
+        if !lines_by_id.contains_key(id) {
+            return Err(RukiniaError::new_with_short_description(
+                id.clone(),
+                format!("Unknown test id '{}'", id),
+            ));
+        }
+    }
+
+    Ok(())
 }
 
-/// Executes a single test expression and handles the evaluation of logical operators.
-///
-/// This function processes a single test expression, checking for parentheses and
-/// logical operators such as AND/OR. It recursively evaluates sub-expressions
-/// and combines results accordingly.
+/// Checks the reports requested for a run before running it.
 ///
 /// # Arguments
-/// * `expression` - The test expression string that needs to be evaluated.
-///
-/// # Example
-/// ```rust,ignore
-/// let result = rukinia_execute_single_test("test1 && test2").await;
-/// ```
+/// * `result_formats` - The reports to write, all fed from the same execution.
 ///
 /// # Errors
-/// Returns a `RukiniaError` if there's an issue with parsing or evaluating the expression.
-///
-pub async fn rukinia_execute_single_test(
-    expression: &str,
-) -> Result<RukiniaResultEntry, RukiniaError> {
-    if let Some((start, end)) = find_outermost_parentheses(expression) {
-        let content = &expression[start + 1..end];
-        let result = Box::pin(rukinia_execute_single_test(content)).await?;
-
-        let result_bool_str = match result.result_type {
-            RukiniaResultType::TestSuccess => RukiniaTrue::get_rukinia_command(),
-            RukiniaResultType::TestFail => RukiniaFalse::get_rukinia_command(),
-        };
-
-        let new_expression = format!(
-            "{}{}{}",
-            &expression[..start],
-            result_bool_str,
-            &expression[end + 1..]
-        );
-
-        let mut final_result = Box::pin(rukinia_execute_single_test(&new_expression)).await?;
-
-        if find_outermost_parentheses(new_expression.as_str()).is_none() {
-            let new_expression_vec = convert_config_line_to_vector_string(new_expression.as_str());
-            if let Some(syntax) = Syntax::find_syntax_as(new_expression_vec).await {
-                final_result.label = syntax.get_argument().unwrap();
-            } else {
-                final_result.label = format!(
-                    "{}({}){}",
-                    &expression[..start],
-                    result.label,
-                    &expression[end + 1..],
-                );
-            }
-        } else {
-            let new_expression_vec = convert_config_line_to_vector_string(new_expression.as_str());
-            if let Some(syntax) = Syntax::find_syntax_as(new_expression_vec).await {
-                final_result.label = syntax.get_argument().unwrap();
-            } else {
-                final_result.label = format!(
-                    "{}({}){}",
-                    &final_result.label[..start],
-                    result.label,
-                    &final_result.label[start + result_bool_str.len()..],
-                );
-            };
+/// Returns a `RukiniaError` if several reports would be written to the same path,
+/// or if a report holding a single document is in `append` mode.
+pub fn rukinia_check_result_formats(result_formats: &[ResultFormat]) -> Result<(), RukiniaError> {
+    let mut formats_by_path: HashMap<&str, &FormatOutput> = HashMap::new();
+
+    for result_format in result_formats {
+        if result_format.options.mode == ReportMode::Append
+            && !result_format.format.supports_append()
+        {
+            return Err(RukiniaError::new(
+                result_format.path.clone(),
+                "Invalid report".to_string(),
+                format!(
+                    "{} reports cannot be appended to, use the truncate or timestamped mode",
+                    result_format.format
+                ),
+            ));
+        }
+        if let Some(format) = formats_by_path.insert(&result_format.path, &result_format.format) {
+            return Err(RukiniaError::new(
+                result_format.path.clone(),
+                "Invalid report".to_string(),
+                format!(
+                    "{} and {} reports are both written to {}",
+                    format, result_format.format, result_format.path
+                ),
+            ));
         }
-
-        return Ok(final_result);
     }
 
-    let formatted_parts = convert_config_line_to_vector_string(expression);
-    evaluate_logical_expression(formatted_parts).await
+    Ok(())
 }
 
-/// Converts a configuration line (string) into a vector of strings based on regex matches.
-///
-/// # Arguments
-/// * `buffer` - The string to be converted into a vector.
-///
-/// # Returns
-/// A vector of strings extracted from the buffer using regex.
-fn convert_config_line_to_vector_string(buffer: &str) -> Vec<String> {
-    RE.captures_iter(buffer)
-        .filter_map(|cap| {
-            cap.iter()
-                .skip(1)
-                .flatten()
-                .next()
-                .map(|m| m.as_str().to_string())
-        })
-        .collect()
+/// A problem found in a test plan without running it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RukiniaPlanProblem {
+    /// Path of the test plan.
+    pub path: String,
+    /// Line (1-based) of the test.
+    pub line: usize,
+    /// Column (1-based, in characters) of the problem.
+    pub column: usize,
+    /// The test, as written in the test plan.
+    pub test: String,
+    pub message: String,
+}
+
+impl fmt::Display for RukiniaPlanProblem {
+    /// Formats the problem as `path:line:column: message`, followed by the test
+    /// and a caret under the column.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Keep the tabs of the test so that the caret lines up with it
+        let indent: String = self
+            .test
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(
+            f,
+            "{}:{}:{}: {}\n    {}\n    {}^",
+            self.path, self.line, self.column, self.message, self.test, indent
+        )
+    }
 }
 
-/// Evaluates logical expressions with operators (AND, OR).
+/// Checks every test of the test plans without running them.
 ///
-/// This function evaluates expressions containing logical operators like AND or OR.
-/// It processes each operator and recursively evaluates sub-expressions.
+/// Each test is parsed, its variables are expanded, the arguments of each task are
+/// checked (see `RukiniaAllTasks::check_arguments`), test IDs must be unique, and every
+/// block must be closed (see `rukinia_find_block_problems`).
 ///
 /// # Arguments
-/// * `expression_parts` - A vector of strings representing parts of a logical expression.
+/// * `plans` - The test plans, each holding one test expression per line.
+/// * `variables` - The variables defined on the command line, see `RukiniaVariables`.
 ///
 /// # Returns
-/// The final result of the evaluated expression.
-async fn evaluate_logical_expression(
-    expression_parts: Vec<String>,
-) -> Result<RukiniaResultEntry, RukiniaError> {
-    let (result, mut remaining_parts) = read_expression_task(expression_parts.clone()).await?;
-    let mut final_result = result.clone();
-
-    while !remaining_parts.is_empty() {
-        let operator = &remaining_parts[0];
-
-        let logical_operator = match operator.parse::<LogicalOperator>() {
-            Ok(op) => op,
-            Err(_) => {
-                return Err(RukiniaError::new(
-                    "Invalid operator".to_string(),
-                    "Failed to parse logical operator".to_string(),
-                    format!("Invalid operator: {}", operator),
-                ));
+/// Every problem found, in the order of the test plans, empty if every test is valid.
+pub fn rukinia_check_test_plans(
+    plans: &[RukiniaTestPlan],
+    variables: &RukiniaVariables,
+) -> Vec<RukiniaPlanProblem> {
+    let mut problems = Vec::new();
+    let mut lines_by_id: HashMap<String, String> = HashMap::new();
+
+    for plan in plans {
+        let mut variables = variables.clone();
+        let mut block_problems = rukinia_find_block_problems(&plan.lines)
+            .into_iter()
+            .peekable();
+        for (index, plan_line) in plan.lines.iter().enumerate() {
+            let line = plan_line.text.as_str();
+            let problem = |column: usize, message: String| RukiniaPlanProblem {
+                path: plan_line.path.clone(),
+                line: plan_line.line,
+                column,
+                test: line.to_string(),
+                message,
+            };
+            while let Some((_, error)) = block_problems.next_if(|(line, _)| *line == index) {
+                problems.push(problem(error.column, error.message));
             }
-        };
 
-        let (new_task_result, new_remaining) =
-            read_expression_task(remaining_parts[1..].to_vec()).await?;
+            let (expression, columns) = match plan_line.parse() {
+                Ok(RukiniaPlanStatement::Test(expression, columns))
+                | Ok(RukiniaPlanStatement::If(expression, columns)) => (expression, columns),
+                Ok(RukiniaPlanStatement::Set { name, value }) => {
+                    if let Err(message) = variables.set(&name, &value) {
+                        problems.push(problem(1, message));
+                    }
+                    continue;
+                }
+                Ok(RukiniaPlanStatement::Else) | Ok(RukiniaPlanStatement::End) => continue,
+                Err(e) => {
+                    let message = match e.hint {
+                        Some(hint) => format!("{}, {}", e.message, hint),
+                        None => e.message,
+                    };
+                    problems.push(problem(e.column, message));
+                    continue;
+                }
+            };
 
-        match logical_operator {
-            LogicalOperator::And => {
-                if final_result.result_type == RukiniaResultType::TestSuccess {
-                    final_result.result_type = new_task_result.result_type;
+            let mut expanded = true;
+            for ((task, arguments), column) in expression.get_tasks().into_iter().zip(columns.tasks)
+            {
+                let arguments = match arguments
+                    .iter()
+                    .map(|argument| variables.expand(argument))
+                    .collect::<Result<Vec<String>, String>>()
+                {
+                    Ok(arguments) => arguments,
+                    Err(message) => {
+                        expanded = false;
+                        problems.push(problem(column, message));
+                        continue;
+                    }
+                };
+                if let Err(message) = task.check_arguments(&arguments) {
+                    problems.push(problem(
+                        column,
+                        format!("{}: {}", task.get_rukinia_command(), message),
+                    ));
                 }
-                final_result.label.push_str(" and ");
-                final_result.label.push_str(new_task_result.label.as_str());
             }
-            LogicalOperator::Or => {
-                if final_result.result_type == RukiniaResultType::TestFail {
-                    final_result.result_type = new_task_result.result_type;
+            // The variables of the `as` labels, those of the arguments being reported above
+            if let (true, Err(message)) = (expanded, expression.expand(&variables)) {
+                problems.push(problem(1, message));
+            }
+
+            let ids = expression.get_ids();
+            for (position, (id, column)) in ids.iter().zip(columns.ids).enumerate() {
+                let location = plan_line.get_location();
+                if position > 0 {
+                    problems.push(problem(
+                        column,
+                        format!("Test already has the id '{}'", ids[0]),
+                    ));
+                } else if let Some(first_location) = lines_by_id.get(id) {
+                    problems.push(problem(
+                        column,
+                        format!(
+                            "Duplicate test id '{}', first used at {}",
+                            id, first_location
+                        ),
+                    ));
+                } else {
+                    lines_by_id.insert(id.clone(), location);
                 }
-                final_result.label.push_str(" or ");
-                final_result.label.push_str(new_task_result.label.as_str());
             }
         }
+    }
+
+    problems
+}
 
-        remaining_parts = new_remaining;
+/// A test of a test plan described without running it, see `rukinia_describe_test_plans`.
+pub struct RukiniaTestDescription {
+    /// Path of the test plan.
+    pub plan: String,
+    /// Line (1-based) of the test.
+    pub line: usize,
+    /// The test, as written in the test plan.
+    pub test: String,
+    /// The nodes of the expression, one per line, indented by depth: the tasks with their
+    /// modifiers and arguments, the groups and the logical operators.
+    pub nodes: Vec<String>,
+    /// Label the result of the test would have, prefixed with its ID.
+    pub label: String,
+    /// The error running the test would raise, if its expression or arguments are invalid.
+    pub error: Option<RukiniaError>,
+}
+
+impl fmt::Display for RukiniaTestDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.plan, self.line, self.test)?;
+        for node in &self.nodes {
+            write!(f, "\n    {}", node)?;
+        }
+        match &self.error {
+            Some(error) => write!(
+                f,
+                "\n    error: {}: {}",
+                error.system_error.as_deref().unwrap_or_default(),
+                error.system_error_message
+            ),
+            None => write!(f, "\n    => {}", self.label),
+        }
     }
-    Ok(final_result)
 }
 
-/// Finds the outermost parentheses in an expression.
+/// Describes the tests of the test plans without running them, e.g. for a dry run.
+///
+/// Every task is built from its arguments (see `RukiniaAllTasks::build`) to find the
+/// label it would give, but the system is never probed. The tests of every branch of
+/// the blocks are described, after the test of their `if`.
 ///
 /// # Arguments
-/// * `expression` - The string containing the expression.
+/// * `plans` - The test plans, each holding one test expression per line.
+/// * `selection` - The tests to describe, by `id`.
+/// * `variables` - The variables defined on the command line, see `RukiniaVariables`.
 ///
 /// # Returns
-/// The indices of the outermost parentheses if found, or `None` if not found.
-fn find_outermost_parentheses(expression: &str) -> Option<(usize, usize)> {
-    let mut start_index = None;
-    let mut balance = 0;
-
-    for (i, ch) in expression.chars().enumerate() {
-        if ch == '(' {
-            if start_index.is_none() {
-                start_index = Some(i);
-            }
-            balance += 1;
-        } else if ch == ')' {
-            balance -= 1;
-            if balance == 0 {
-                if let Some(start) = start_index {
-                    return Some((start, i));
+/// The description of every selected test, in the order of the test plans.
+pub fn rukinia_describe_test_plans(
+    plans: &[RukiniaTestPlan],
+    selection: &TestSelection,
+    variables: &RukiniaVariables,
+) -> Vec<RukiniaTestDescription> {
+    let mut descriptions = Vec::new();
+
+    for plan in plans {
+        let mut variables = variables.clone();
+        for plan_line in &plan.lines {
+            let line = plan_line.text.as_str();
+            let condition = plan_line.get_block_keyword() == Some(IF_KEYWORD);
+            let expression = match rukinia_plan_line_statement(plan_line, &variables) {
+                Ok(RukiniaPlanStatement::Test(expression, _))
+                | Ok(RukiniaPlanStatement::If(expression, _)) => Ok(expression),
+                Ok(RukiniaPlanStatement::Set { name, value }) => {
+                    match variables.set(&name, &value) {
+                        Ok(()) => continue,
+                        Err(message) => Err(variable_error(line, message)),
+                    }
                 }
+                Ok(RukiniaPlanStatement::Else) | Ok(RukiniaPlanStatement::End) => continue,
+                Err(error) => Err(error),
+            };
+            let mut description = RukiniaTestDescription {
+                plan: plan_line.path.clone(),
+                line: plan_line.line,
+                test: line.to_string(),
+                nodes: Vec::new(),
+                label: String::new(),
+                error: None,
+            };
+
+            match expression {
+                Ok(expression) => {
+                    let id = expression.get_id();
+                    if !selection.is_selected(id.as_deref()) {
+                        continue;
+                    }
+                    match describe_expression(&expression, 0, &mut description.nodes) {
+                        Ok(label) if condition => {
+                            description.label = format!("{} {}", IF_KEYWORD, label)
+                        }
+                        Ok(label) => description.label = display_label(id.as_deref(), &label),
+                        Err(error) => description.error = Some(error),
+                    }
+                }
+                Err(error) if selection.is_selected(error.get_id()) => {
+                    description.error = Some(error)
+                }
+                Err(_) => continue,
             }
+
+            descriptions.push(description);
         }
     }
-    None
+
+    descriptions
 }
 
-/// Reads a task expression and returns the corresponding result and remaining parts.
+/// Describes a parsed expression tree, node by node, as `evaluate_logical_expression` would walk it.
 ///
-/// This function processes the parts of the expression and determines if it's a valid task
-/// or if more syntax extraction is needed.
+/// # Returns
+/// The label the result of the expression would have.
+fn describe_expression(
+    expression: &Expression,
+    depth: usize,
+    nodes: &mut Vec<String>,
+) -> Result<String, RukiniaError> {
+    let indent = "  ".repeat(depth);
+    match expression {
+        Expression::Task {
+            task,
+            arguments,
+            syntax,
+        } => {
+            let built = task.build(arguments.clone(), syntax.clone())?;
+            nodes.push(format!(
+                "{}{}{} {:?}",
+                indent,
+                syntax.get_prefix(),
+                task.get_rukinia_command(),
+                arguments
+            ));
+            Ok(syntax.get_as().unwrap_or_else(|| built.display_format()))
+        }
+        Expression::Group { expression, syntax } => {
+            nodes.push(format!("{}{}(", indent, syntax.get_prefix()));
+            let label = describe_expression(expression, depth + 1, nodes)?;
+            nodes.push(format!("{})", indent));
+            Ok(group_label(syntax, &label))
+        }
+        Expression::Logical {
+            operator,
+            left,
+            right,
+        } => {
+            let left_label = describe_expression(left, depth, nodes)?;
+            nodes.push(format!("{}{}", indent, operator));
+            let right_label = describe_expression(right, depth, nodes)?;
+            Ok(format!("{} {} {}", left_label, operator, right_label))
+        }
+        Expression::Conditional {
+            kind,
+            condition,
+            expression,
+        } => {
+            nodes.push(format!("{}{}", indent, kind));
+            describe_expression(condition, depth + 1, nodes)?;
+            describe_expression(expression, depth, nodes)
+        }
+    }
+}
+
+/// Executes all tests found in the test plans.
+///
+/// This function iterates through each line of the test plans, executes
+/// each test expression, and sends the events of the run to the reporters.
+/// The test of an `if` is evaluated without being reported, and decides which
+/// lines of its block are run; the lines of the other branch are ignored. An
+/// error evaluating it is reported, and neither branch is run.
 ///
 /// # Arguments
-/// * `parts` - The vector of strings representing parts of the expression.
+/// * `plans` - The test plans, each holding one test expression per line.
+/// * `reporters` - The sinks receiving the results (e.g., console, CSV, JUnit), all fed from the same execution.
+/// * `selection` - The tests to run, by `id`; the other tests are not counted.
+/// * `variables` - The variables defined on the command line, see `RukiniaVariables`.
 ///
 /// # Returns
-/// A result containing a `RukiniaResultEntry` and the remaining parts of the expression.
-async fn read_expression_task(
-    parts: Vec<String>,
-) -> Result<(RukiniaResultEntry, Vec<String>), RukiniaError> {
-    let mut syntax = SyntaxForTrait::Arguments(Vec::new());
-    let mut remaining_parts = parts.clone();
-    let mut bool_syntax_extracted = false;
-
-    while !remaining_parts.is_empty() {
-        let word = &remaining_parts[0];
-        match RukiniaAllTasks::from_str(word.as_str()) {
-            Ok(_) => {
-                let (task_result, arguments, remaining) = create_task(remaining_parts.clone())?;
-                let rukinia_valid = execute_task(task_result, arguments.clone(), syntax).await?;
-                return Ok((rukinia_valid, remaining));
-            }
-            Err(_) => {
-                if bool_syntax_extracted {
-                    return Err(RukiniaError::new_with_short_description(
-                         parts.join(" "),
-                        "Failed to parse rukinia task".to_string(),
-                    ));
+/// The number of passed, failed and errored tests, and the time spent on each test.
+///
+/// # Example
+/// ```no_run
+/// # use rukinia::core::configuration::RukiniaTestPlan;
+/// # use rukinia::core::reporter::rukinia_reporters;
+/// # use rukinia::core::run_tasks::{rukinia_execute_all_tests, TestSelection};
+/// # use rukinia::core::save_test_result::ConsoleOutput;
+/// # use rukinia::core::variables::RukiniaVariables;
+/// # async fn run() -> Result<(), rukinia::core::rukinia_result::RukiniaError> {
+/// let plans = vec![RukiniaTestPlan::new("-", "rukinia_user root\nrukinia_kmod wl18xx\n")?];
+/// let mut reporters = rukinia_reporters(ConsoleOutput::Text, &[]);
+/// let selection = TestSelection::default();
+/// let variables = RukiniaVariables::default();
+/// let outcome = rukinia_execute_all_tests(&plans, &mut reporters, &selection, &variables).await;
+/// # Ok(())
+/// # }
+/// ```
+///
+pub async fn rukinia_execute_all_tests(
+    plans: &[RukiniaTestPlan],
+    reporters: &mut [Box<dyn Reporter>],
+    selection: &TestSelection,
+    variables: &RukiniaVariables,
+) -> RukiniaRunOutcome {
+    let mut outcome = RukiniaRunOutcome::default();
+
+    let run = RukiniaRunInfo::now();
+    notify(reporters, |reporter| reporter.on_run_start(&run));
+
+    for plan in plans {
+        notify(reporters, |reporter| reporter.on_plan_start(plan));
+
+        let mut variables = variables.clone();
+        let mut blocks = PlanBlocks::default();
+        for plan_line in &plan.lines {
+            let line = plan_line.text.as_str();
+            let expression = match rukinia_plan_line_statement(plan_line, &variables) {
+                Ok(RukiniaPlanStatement::Else) => {
+                    blocks.switch();
+                    continue;
+                }
+                Ok(RukiniaPlanStatement::End) => {
+                    blocks.close();
+                    continue;
+                }
+                _ if !blocks.is_active() => {
+                    blocks.follow(plan_line.get_block_keyword());
+                    continue;
+                }
+                Ok(RukiniaPlanStatement::If(expression, _)) => {
+                    match evaluate_logical_expression(&expression).await {
+                        Ok(result) => {
+                            blocks.open(Some(result.result_type == RukiniaResultType::TestSuccess));
+                            continue;
+                        }
+                        Err(error) => {
+                            blocks.open(None);
+                            Err(error)
+                        }
+                    }
+                }
+                Ok(RukiniaPlanStatement::Set { name, value }) => {
+                    match variables.set(&name, &value) {
+                        Ok(()) => continue,
+                        Err(message) => Err(variable_error(line, message)),
+                    }
                 }
-                let (new_syntax_trait, new_remaining) =
-                    SyntaxForTrait::extract_syntax(remaining_parts).await?;
-                syntax = new_syntax_trait;
-                remaining_parts = new_remaining;
-                bool_syntax_extracted = true;
+                Ok(RukiniaPlanStatement::Test(expression, _)) => Ok(expression),
+                Err(error) => {
+                    blocks.follow(plan_line.get_block_keyword());
+                    Err(error)
+                }
+            };
+            let id = match &expression {
+                Ok(expression) => expression.get_id(),
+                Err(error) => error.get_id().map(str::to_string),
+            };
+            if !selection.is_selected(id.as_deref()) {
+                continue;
             }
+
+            let test = RukiniaTestStart {
+                plan: &plan_line.path,
+                line: plan_line.line,
+                test: line,
+                expression: expression.as_ref().ok(),
+            };
+            notify(reporters, |reporter| reporter.on_test_start(&test));
+
+            let start_time = Instant::now();
+            let result = match expression {
+                Ok(expression) => rukinia_execute_expression(&expression).await,
+                Err(error) => Err(error),
+            };
+            let duration = start_time.elapsed();
+
+            match result {
+                Ok(mut rukinia_entry) => {
+                    rukinia_entry.duration = duration;
+                    rukinia_entry.location = Some(plan_line.get_location());
+                    outcome.add_result(&rukinia_entry);
+                    notify(reporters, |reporter| {
+                        reporter.on_test_result(&rukinia_entry)
+                    });
+                }
+                Err(rukinia_error) => {
+                    let rukinia_error = rukinia_error.with_location(plan_line.get_location());
+                    outcome.add_error(&rukinia_error, duration);
+                    notify(reporters, |reporter| {
+                        reporter.on_error(&rukinia_error, duration)
+                    });
+                }
+            };
         }
+
+        notify(reporters, |reporter| reporter.on_plan_end(plan));
     }
-    Err(RukiniaError::new_with_short_description(
-        format!("No task in test: {}", parts.join(" ")),
-        "Failed to parse rukinia task".to_string(),
-    ))
+
+    notify(reporters, |reporter| reporter.on_run_end(&outcome));
+
+    outcome
 }
 
-/// Creates a task from a set of parts in the expression.
+/// Sends an event to every reporter, printing the errors of those failing to handle it.
+fn notify(
+    reporters: &mut [Box<dyn Reporter>],
+    event: impl Fn(&mut dyn Reporter) -> ReporterResult,
+) {
+    for reporter in reporters.iter_mut() {
+        if let Err(e) = event(reporter.as_mut()) {
+            RukiniaError::new(
+                format!("Write {}", reporter.name()),
+                "Failed to write output".to_string(),
+                e.to_string(),
+            )
+            .display_result();
+        }
+    }
+}
+
+/// Executes a single test expression and handles the evaluation of logical operators.
 ///
-/// This function extracts the task name, its arguments, and any remaining parts of the
-/// expression that might represent logical operators or additional syntax.
+/// This function parses the test expression into an `Expression` tree, then
+/// walks it to execute every task and combine their results.
 ///
 /// # Arguments
-/// * `parts` - A vector of strings representing the parts of the expression, where the first
-///   part is the task name, followed by its arguments and potential operators.
+/// * `expression` - The test expression string that needs to be evaluated.
 ///
-/// # Returns
-/// A result containing:
-/// - The task (`RukiniaAllTasks`) that corresponds to the task name found in the expression.
-/// - A vector of strings representing the arguments passed to the task.
-/// - A vector of strings containing the remaining parts (typically operators and additional expressions).
+/// # Example
+/// ```rust,ignore
+/// let result = rukinia_execute_single_test("rukinia_user root and rukinia_group root").await;
+/// ```
 ///
 /// # Errors
-/// Returns a `RukiniaError` if the task name is invalid or cannot be parsed.
-fn create_task(
-    parts: Vec<String>,
-) -> Result<(RukiniaAllTasks, Vec<String>, Vec<String>), RukiniaError> {
-    let task = match RukiniaAllTasks::from_str(&parts[0]) {
-        Ok(task) => task,
-        Err(_) => {
-            return Err(RukiniaError::new(
-                "Invalid task name".to_string(),
-                "Failed to parse rukinia task".to_string(),
-                format!("Invalid task: {}", parts[0]),
-            ));
-        }
+/// Returns a `RukiniaError` if there's an issue with parsing or evaluating the expression.
+///
+pub async fn rukinia_execute_single_test(
+    expression: &str,
+) -> Result<RukiniaResultEntry, RukiniaError> {
+    let tree = parse_expression(expression)?
+        .expand(&RukiniaVariables::default())
+        .map_err(|message| {
+            RukiniaError::new(
+                expression.to_string(),
+                "Failed to expand variables".to_string(),
+                message,
+            )
+        })?;
+    rukinia_execute_expression(&tree).await
+}
+
+/// Parses a line of a test plan, with the variables of its test expanded.
+///
+/// # Arguments
+/// * `plan_line` - The line of the test plan.
+/// * `variables` - The variables of the test plan, see `RukiniaVariables::expand`; a
+///   `set` statement is returned as written for the caller to apply it.
+///
+/// # Errors
+/// Returns the error running the line would raise if it cannot be parsed or uses an
+/// undefined variable.
+pub fn rukinia_plan_line_statement(
+    plan_line: &RukiniaPlanLine,
+    variables: &RukiniaVariables,
+) -> Result<RukiniaPlanStatement, RukiniaError> {
+    let line = plan_line.text.as_str();
+    let expand = |expression: &Expression| {
+        expression.expand(variables).map_err(|message| {
+            RukiniaError::new(
+                line.to_string(),
+                "Failed to expand variables".to_string(),
+                message,
+            )
+            .with_id(expression.get_id())
+        })
     };
+    match plan_line.parse() {
+        Ok(RukiniaPlanStatement::Test(expression, columns)) => {
+            Ok(RukiniaPlanStatement::Test(expand(&expression)?, columns))
+        }
+        Ok(RukiniaPlanStatement::If(expression, columns)) => {
+            Ok(RukiniaPlanStatement::If(expand(&expression)?, columns))
+        }
+        Ok(statement) => Ok(statement),
+        Err(e) => Err(e.to_rukinia_error(line, "Failed to parse rukinia test")),
+    }
+}
+
+/// Error raised by a `set` statement that cannot be applied.
+fn variable_error(line: &str, message: String) -> RukiniaError {
+    RukiniaError::new(line.to_string(), "Invalid variable".to_string(), message)
+}
+
+/// The blocks of a test plan being run, see `RukiniaPlanStatement::If`.
+#[derive(Default)]
+struct PlanBlocks {
+    /// For every open block, from the outermost: the result of its test, `None` if it
+    /// was not evaluated, and whether its `else` was reached.
+    blocks: Vec<(Option<bool>, bool)>,
+}
+
+impl PlanBlocks {
+    /// Returns true if the lines read now are run.
+    fn is_active(&self) -> bool {
+        self.blocks
+            .iter()
+            .all(|&(passed, in_else)| passed == Some(!in_else))
+    }
 
-    // Collect arguments until we hit a logical operator or end of parts
-    let mut arguments = Vec::new();
-    let mut remaining_parts = Vec::new();
-    let mut found_operator = false;
+    fn open(&mut self, passed: Option<bool>) {
+        self.blocks.push((passed, false));
+    }
 
-    for part in parts.iter().skip(1) {
-        if LogicalOperator::from_str(part).is_ok() {
-            found_operator = true;
+    fn switch(&mut self) {
+        if let Some(block) = self.blocks.last_mut() {
+            block.1 = true;
         }
+    }
+
+    fn close(&mut self) {
+        self.blocks.pop();
+    }
 
-        if found_operator {
-            remaining_parts.push(part.clone());
-        } else {
-            arguments.push(part.clone());
+    /// Follows a line that is not run or cannot be parsed from its block keyword, so
+    /// a malformed `if`, `else` or `end` still opens, switches or closes its block.
+    fn follow(&mut self, keyword: Option<&str>) {
+        match keyword {
+            Some(IF_KEYWORD) => self.open(None),
+            Some(ELSE_KEYWORD) => self.switch(),
+            Some(END_KEYWORD) => self.close(),
+            _ => {}
         }
     }
+}
 
-    Ok((task, arguments, remaining_parts))
+/// Evaluates a parsed test expression, attaching its `id` to the result.
+async fn rukinia_execute_expression(
+    expression: &Expression,
+) -> Result<RukiniaResultEntry, RukiniaError> {
+    let id = expression.get_id();
+    match evaluate_logical_expression(expression).await {
+        Ok(mut result) => {
+            result.id = id;
+            Ok(result)
+        }
+        Err(error) => Err(error.with_id(id)),
+    }
+}
+
+/// Evaluates a parsed expression tree.
+///
+/// Tasks are executed with the modifiers prefixing them, groups apply their own
+/// `not`/`as` modifiers to the result of the nested expression, and logical nodes
+/// combine both sides from left to right. Every task is executed, even when the
+/// result of a logical node is already known, so the label describes the whole test.
+/// The condition of a conditional node is evaluated first; when it skips the test,
+/// the test is only built to find its label and the reason is given in the details.
+///
+/// # Arguments
+/// * `expression` - The expression tree to evaluate.
+///
+/// # Returns
+/// The final result of the evaluated expression.
+async fn evaluate_logical_expression(
+    expression: &Expression,
+) -> Result<RukiniaResultEntry, RukiniaError> {
+    match expression {
+        Expression::Task {
+            task,
+            arguments,
+            syntax,
+        } => {
+            let mut result = execute_task(task.clone(), arguments.clone(), syntax.clone()).await?;
+            result.tasks = vec![RukiniaTaskCall {
+                task: task.get_rukinia_command().to_string(),
+                arguments: arguments.clone(),
+                syntax: syntax.clone(),
+            }];
+            result.syntax = syntax.clone();
+            Ok(result)
+        }
+        Expression::Group { expression, syntax } => {
+            let mut result = Box::pin(evaluate_logical_expression(expression)).await?;
+            result.syntax = syntax.clone();
+            if syntax.contains_not() {
+                result.result_type = match result.result_type {
+                    RukiniaResultType::TestSuccess => RukiniaResultType::TestFail,
+                    RukiniaResultType::TestFail => RukiniaResultType::TestSuccess,
+                    RukiniaResultType::TestSkip => RukiniaResultType::TestSkip,
+                };
+            }
+            result.label = group_label(syntax, &result.label);
+            Ok(result)
+        }
+        Expression::Logical {
+            operator,
+            left,
+            right,
+        } => {
+            let mut final_result = Box::pin(evaluate_logical_expression(left)).await?;
+            let new_task_result = Box::pin(evaluate_logical_expression(right)).await?;
+            final_result.merge_details(&new_task_result);
+
+            // A skipped side, e.g. reported by a plugin, does not count
+            match operator {
+                _ if final_result.result_type == RukiniaResultType::TestSkip => {
+                    final_result.result_type = new_task_result.result_type;
+                }
+                _ if new_task_result.result_type == RukiniaResultType::TestSkip => {}
+                LogicalOperator::And => {
+                    if final_result.result_type == RukiniaResultType::TestSuccess {
+                        final_result.result_type = new_task_result.result_type;
+                    }
+                }
+                LogicalOperator::Or => {
+                    if final_result.result_type == RukiniaResultType::TestFail {
+                        final_result.result_type = new_task_result.result_type;
+                    }
+                }
+            }
+            final_result.label = format!(
+                "{} {} {}",
+                final_result.label, operator, new_task_result.label
+            );
+            final_result.tasks.extend(new_task_result.tasks);
+            final_result.syntax = SyntaxForTrait::Arguments(Vec::new());
+
+            Ok(final_result)
+        }
+        Expression::Conditional {
+            kind,
+            condition,
+            expression,
+        } => {
+            let condition_result = Box::pin(evaluate_logical_expression(condition)).await?;
+            let passed = condition_result.result_type == RukiniaResultType::TestSuccess;
+            if !kind.skips(passed) {
+                return Box::pin(evaluate_logical_expression(expression)).await;
+            }
+
+            let label = describe_expression(expression, 0, &mut Vec::new())?;
+            let mut result = RukiniaResultEntry::new(RukiniaResultType::TestSkip, label);
+            result.details = Some(format!(
+                "{} {} {}",
+                kind,
+                condition_result.label,
+                if passed { "passed" } else { "failed" }
+            ));
+            Ok(result)
+        }
+    }
+}
+
+/// Returns the label of a group: its `as` label, or the label of the nested expression
+/// in parentheses.
+fn group_label(syntax: &SyntaxForTrait, label: &str) -> String {
+    syntax.get_as().unwrap_or_else(|| {
+        format!(
+            "{}({})",
+            if syntax.contains_not() { "not " } else { "" },
+            label
+        )
+    })
 }
 
 /// Executes the specified task with the given arguments and syntax.
@@ -429,37 +960,3 @@ async fn execute_task(
 ) -> Result<RukiniaResultEntry, RukiniaError> {
     rukinia_task.execute(arguments, syntax).await
 }
-
-/// Writes the result of a task to a specified output format (CSV, Text, JUnit).
-///
-/// # Arguments
-/// * `task_result` - The result of a test task.
-/// * `format` - The format in which the result should be written (e.g., CSV, JUnit).
-async fn write_result(
-    task_result: &RukiniaResultEntry,
-    format: &ResultFormat,
-) -> Result<(), Box<dyn Error>> {
-    match format.format {
-        FormatOutput::Csv => task_result.write_csv(&format.path).await?,
-        FormatOutput::TextFile => task_result.write_text(&format.path).await?,
-        FormatOutput::JUnit => task_result.write_junit(&format.path).await?,
-    }
-    Ok(())
-}
-
-/// Handles writing error results to the specified format (CSV, Text, JUnit).
-///
-/// # Arguments
-/// * `error` - The error result to be written.
-/// * `format` - The format in which the error result should be written.
-async fn write_result_error(
-    error: &RukiniaError,
-    format: &ResultFormat,
-) -> Result<(), Box<dyn Error>> {
-    match format.format {
-        FormatOutput::Csv => error.write_csv(&format.path).await?,
-        FormatOutput::TextFile => error.write_text(&format.path).await?,
-        FormatOutput::JUnit => error.write_junit(&format.path).await?,
-    }
-    Ok(())
-}
@@ -1,50 +1,292 @@
 use csv::WriterBuilder;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
 use std::fmt;
 use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::core::rukinia_result::{
+    format_duration, RukiniaError, RukiniaResultEntry, RukiniaResultType, RukiniaRunInfo,
+    RukiniaTaskCall,
+};
+
 #[derive(Serialize)]
 pub struct CsvTestResult<'a> {
+    #[serde(rename = "ID")]
+    pub id: &'a str,
     #[serde(rename = "TEST MESSAGE")]
     pub label: &'a str,
     #[serde(rename = "RESULT")]
     pub result: &'a str,
+    #[serde(rename = "DURATION (s)")]
+    pub duration: String,
+    #[serde(rename = "RUN ID")]
+    pub run_id: &'a str,
+    #[serde(rename = "RUN START")]
+    pub run_start: String,
+    #[serde(rename = "HOSTNAME")]
+    pub hostname: &'a str,
+    #[serde(rename = "RUKINIA VERSION")]
+    pub version: &'a str,
 }
 
-#[derive(Clone)]
+/// A report written by a run: its format, its path and the options of its format.
+///
+/// Several reports can be fed from the same execution, e.g. JUnit for the CI and
+/// text for humans.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct ResultFormat {
     pub format: FormatOutput,
     pub path: String,
+    #[serde(flatten)]
+    pub options: ResultFormatOptions,
+}
+
+impl ResultFormat {
+    /// Creates a report with the default options.
+    pub fn new(format: FormatOutput, path: String) -> Self {
+        ResultFormat {
+            format,
+            path,
+            options: ResultFormatOptions::default(),
+        }
+    }
+}
+
+/// Options of a report, each one only applying to some formats.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ResultFormatOptions {
+    /// Name of the JUnit test suite and class name of its test cases.
+    pub suite_name: String,
+    /// Indent the JSON report, written on a single line otherwise.
+    pub pretty: bool,
+    /// How the report treats the content of its file.
+    pub mode: ReportMode,
+}
+
+impl Default for ResultFormatOptions {
+    fn default() -> Self {
+        ResultFormatOptions {
+            suite_name: JUnitReport::SUITE_NAME.to_string(),
+            pretty: true,
+            mode: ReportMode::Truncate,
+        }
+    }
+}
+
+impl ResultFormatOptions {
+    /// Sets the option `key` from its textual `value`, e.g. given on the command line.
+    ///
+    /// # Errors
+    /// Returns a message if the option is unknown or its value is invalid.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "suite_name" => self.suite_name = value.to_string(),
+            "pretty" => {
+                self.pretty = value.parse().map_err(|_| {
+                    format!(
+                        "invalid value '{}' for pretty, expected true or false",
+                        value
+                    )
+                })?
+            }
+            "mode" => {
+                self.mode = ReportMode::from_str(value).map_err(|_| {
+                    format!(
+                        "invalid value '{}' for mode, expected truncate, append or timestamped",
+                        value
+                    )
+                })?
+            }
+            _ => return Err(format!("unknown report option '{}'", key)),
+        }
+        Ok(())
+    }
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
 pub enum FormatOutput {
+    #[serde(rename = "csv")]
     Csv,
+    #[serde(rename = "text")]
     TextFile,
+    #[serde(rename = "junit")]
     JUnit,
+    #[serde(rename = "json")]
+    Json,
+    #[serde(rename = "jsonl")]
+    JsonLines,
+    #[serde(rename = "tap")]
+    Tap,
 }
 
 impl fmt::Display for FormatOutput {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            FormatOutput::Csv => write!(f, "Csv"),
-            FormatOutput::TextFile => write!(f, "TextFile"),
-            FormatOutput::JUnit => write!(f, "JUnit"),
+            FormatOutput::Csv => write!(f, "csv"),
+            FormatOutput::TextFile => write!(f, "text"),
+            FormatOutput::JUnit => write!(f, "junit"),
+            FormatOutput::Json => write!(f, "json"),
+            FormatOutput::JsonLines => write!(f, "jsonl"),
+            FormatOutput::Tap => write!(f, "tap"),
         }
     }
 }
 
-impl CsvTestResult<'_> {
-    pub async fn append_csv_result(&self, csv_path: &str) -> Result<(), csv::Error> {
-        let file_exists = Path::new(csv_path).exists();
+/// How a report treats the file it is written to.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportMode {
+    /// Replace the content of the file.
+    #[default]
+    Truncate,
+    /// Add the run after the content of the file, for line-based formats only.
+    Append,
+    /// Write a new file, whose name holds the start time of the run.
+    Timestamped,
+}
+
+impl ReportMode {
+    /// Returns the path of the file written by `run` for a report configured with `path`.
+    ///
+    /// A timestamped report inserts the start time of the run before the extension,
+    /// e.g. `rukinia-20240502T134512Z.xml` for `rukinia.xml`.
+    pub fn get_path(&self, path: &str, run: &RukiniaRunInfo) -> String {
+        match self {
+            ReportMode::Truncate | ReportMode::Append => path.to_string(),
+            ReportMode::Timestamped => {
+                let timestamp = run.get_start_time().replace(['-', ':'], "");
+                let path = Path::new(path);
+                match (path.file_stem(), path.extension()) {
+                    (Some(stem), Some(extension)) => path
+                        .with_file_name(format!(
+                            "{}-{}.{}",
+                            stem.to_string_lossy(),
+                            timestamp,
+                            extension.to_string_lossy()
+                        ))
+                        .to_string_lossy()
+                        .into_owned(),
+                    _ => format!("{}-{}", path.to_string_lossy(), timestamp),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for ReportMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportMode::Truncate => write!(f, "truncate"),
+            ReportMode::Append => write!(f, "append"),
+            ReportMode::Timestamped => write!(f, "timestamped"),
+        }
+    }
+}
+
+impl FromStr for ReportMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "truncate" => Ok(ReportMode::Truncate),
+            "append" => Ok(ReportMode::Append),
+            "timestamped" => Ok(ReportMode::Timestamped),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FormatOutput {
+    /// Returns true if runs can be appended to a report in this format, which
+    /// holds one line per test instead of a single document.
+    pub fn supports_append(&self) -> bool {
+        matches!(
+            self,
+            FormatOutput::Csv | FormatOutput::TextFile | FormatOutput::JsonLines
+        )
+    }
+
+    /// Returns the file extension of the reports in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FormatOutput::Csv => "csv",
+            FormatOutput::TextFile => "txt",
+            FormatOutput::JUnit => "xml",
+            FormatOutput::Json => "json",
+            FormatOutput::JsonLines => "jsonl",
+            FormatOutput::Tap => "tap",
+        }
+    }
+}
+
+impl FromStr for FormatOutput {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(FormatOutput::Csv),
+            "text" => Ok(FormatOutput::TextFile),
+            "junit" => Ok(FormatOutput::JUnit),
+            "json" => Ok(FormatOutput::Json),
+            "jsonl" => Ok(FormatOutput::JsonLines),
+            "tap" => Ok(FormatOutput::Tap),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Format of the results printed on the standard output while tests run.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleOutput {
+    /// Coloured `[PASS]`/`[FAIL]`/`[ERROR]` lines followed by a summary.
+    Text,
+    /// A TAP stream, without any other output on stdout.
+    Tap,
+    /// LAVA test case signals, without any other output on stdout.
+    Lava,
+}
+
+impl fmt::Display for ConsoleOutput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConsoleOutput::Text => write!(f, "text"),
+            ConsoleOutput::Tap => write!(f, "tap"),
+            ConsoleOutput::Lava => write!(f, "lava"),
+        }
+    }
+}
+
+impl FromStr for ConsoleOutput {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(ConsoleOutput::Text),
+            "tap" => Ok(ConsoleOutput::Tap),
+            "lava" => Ok(ConsoleOutput::Lava),
+            _ => Err(()),
+        }
+    }
+}
 
+impl CsvTestResult<'_> {
+    /// Appends the test to a CSV file, after the header if the file is empty.
+    pub fn append_csv_result(&self, csv_path: &str) -> Result<(), csv::Error> {
         let file = OpenOptions::new()
             .append(true)
             .create(true)
             .open(csv_path)?;
+        let file_is_empty = file.metadata()?.len() == 0;
 
         let mut wtr = WriterBuilder::new()
-            .has_headers(!file_exists)
+            .has_headers(file_is_empty)
             .from_writer(file);
 
         wtr.serialize(self)?;
@@ -52,3 +294,777 @@ impl CsvTestResult<'_> {
         Ok(())
     }
 }
+
+/// Outcome of a single JUnit test case.
+enum JUnitOutcome {
+    Success,
+    Failure,
+    Skipped {
+        message: String,
+    },
+    Error {
+        error_type: String,
+        message: String,
+        details: String,
+    },
+}
+
+/// A single `<testcase>` element of a JUnit report.
+struct JUnitTestCase {
+    name: String,
+    id: Option<String>,
+    location: Option<String>,
+    duration: Duration,
+    outcome: JUnitOutcome,
+    details: Option<String>,
+    metrics: BTreeMap<String, f64>,
+}
+
+/// JUnit XML report buffering every test of a run.
+///
+/// JUnit consumers (Jenkins, GitLab) need the counters on the `<testsuite>`
+/// element, so the whole run is collected before the document is written.
+pub struct JUnitReport {
+    suite_name: String,
+    run: Option<RukiniaRunInfo>,
+    testcases: Vec<JUnitTestCase>,
+}
+
+impl Default for JUnitReport {
+    fn default() -> Self {
+        JUnitReport::new(Self::SUITE_NAME)
+    }
+}
+
+impl JUnitReport {
+    pub const SUITE_NAME: &'static str = "rukinia";
+
+    /// Creates an empty report whose test suite is named `suite_name`.
+    pub fn new(suite_name: &str) -> Self {
+        JUnitReport {
+            suite_name: suite_name.to_string(),
+            run: None,
+            testcases: Vec::new(),
+        }
+    }
+
+    /// Records the run in the test suite: its timestamp, hostname, run ID and rukinia version.
+    pub fn set_run(&mut self, run: &RukiniaRunInfo) {
+        self.run = Some(run.clone());
+    }
+
+    /// Adds a test that produced a result.
+    pub fn add_result(&mut self, entry: &RukiniaResultEntry) {
+        self.testcases.push(JUnitTestCase {
+            name: entry.label.clone(),
+            id: entry.id.clone(),
+            location: entry.location.clone(),
+            duration: entry.duration,
+            outcome: match entry.result_type {
+                RukiniaResultType::TestSuccess => JUnitOutcome::Success,
+                RukiniaResultType::TestFail => JUnitOutcome::Failure,
+                RukiniaResultType::TestSkip => JUnitOutcome::Skipped {
+                    message: entry.details.clone().unwrap_or_default(),
+                },
+            },
+            details: entry.details.clone(),
+            metrics: entry.metrics.clone(),
+        });
+    }
+
+    /// Adds a test that raised a `RukiniaError`.
+    pub fn add_error(&mut self, error: &RukiniaError, duration: Duration) {
+        self.testcases.push(JUnitTestCase {
+            name: error.test_command.clone(),
+            id: error.get_id().map(str::to_string),
+            location: error.get_location().map(str::to_string),
+            duration,
+            outcome: JUnitOutcome::Error {
+                error_type: error
+                    .system_error
+                    .clone()
+                    .unwrap_or_else(|| "SystemError".to_string()),
+                message: error.system_error_message.clone(),
+                details: error.label.clone(),
+            },
+            details: None,
+            metrics: BTreeMap::new(),
+        });
+    }
+
+    fn count(&self, predicate: fn(&JUnitOutcome) -> bool) -> usize {
+        self.testcases
+            .iter()
+            .filter(|testcase| predicate(&testcase.outcome))
+            .count()
+    }
+
+    /// Renders the whole report as a JUnit XML document.
+    pub fn to_xml(&self) -> String {
+        let tests = self.testcases.len();
+        let failures = self.count(|outcome| matches!(outcome, JUnitOutcome::Failure));
+        let errors = self.count(|outcome| matches!(outcome, JUnitOutcome::Error { .. }));
+        let skipped = self.count(|outcome| matches!(outcome, JUnitOutcome::Skipped { .. }));
+        let time = format_duration(self.testcases.iter().map(|testcase| testcase.duration).sum());
+
+        let suite_name = xml_escape(&self.suite_name);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\">\n",
+            suite_name, tests, failures, errors, time
+        ));
+        let run_attributes = match &self.run {
+            Some(run) => format!(
+                " timestamp=\"{}\" hostname=\"{}\"",
+                run.get_start_time().trim_end_matches('Z'),
+                xml_escape(&run.hostname)
+            ),
+            None => String::new(),
+        };
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\"{}>\n",
+            suite_name,
+            tests,
+            failures,
+            errors,
+            skipped,
+            time,
+            run_attributes
+        ));
+        if let Some(run) = &self.run {
+            xml.push_str(&format!(
+                "    <properties>\n      <property name=\"run_id\" value=\"{}\" />\n      <property name=\"rukinia_version\" value=\"{}\" />\n    </properties>\n",
+                xml_escape(&run.run_id),
+                xml_escape(&run.version)
+            ));
+        }
+
+        for testcase in &self.testcases {
+            let attributes = format!(
+                "name=\"{}\" classname=\"{}\" time=\"{}\"",
+                xml_escape(&testcase.name),
+                suite_name,
+                format_duration(testcase.duration)
+            );
+            let mut children = String::new();
+            let mut properties: Vec<(String, String)> = Vec::new();
+            if let Some(id) = &testcase.id {
+                properties.push(("id".to_string(), id.clone()));
+            }
+            if let Some(location) = &testcase.location {
+                properties.push(("location".to_string(), location.clone()));
+            }
+            for (name, value) in &testcase.metrics {
+                properties.push((format!("metric.{}", name), value.to_string()));
+            }
+            if !properties.is_empty() {
+                children.push_str("      <properties>\n");
+                for (name, value) in properties {
+                    children.push_str(&format!(
+                        "        <property name=\"{}\" value=\"{}\" />\n",
+                        xml_escape(&name),
+                        xml_escape(&value)
+                    ));
+                }
+                children.push_str("      </properties>\n");
+            }
+            match &testcase.outcome {
+                JUnitOutcome::Success => {}
+                JUnitOutcome::Failure => {
+                    children.push_str(&format!(
+                        "      <failure message=\"{}\" type=\"TestFail\" />\n",
+                        xml_escape(&testcase.name)
+                    ));
+                }
+                JUnitOutcome::Skipped { message } => {
+                    children.push_str(&format!(
+                        "      <skipped message=\"{}\" />\n",
+                        xml_escape(message)
+                    ));
+                }
+                JUnitOutcome::Error {
+                    error_type,
+                    message,
+                    details,
+                } => {
+                    children.push_str(&format!(
+                        "      <error message=\"{}\" type=\"{}\">{}</error>\n",
+                        xml_escape(message),
+                        xml_escape(error_type),
+                        xml_escape(details)
+                    ));
+                }
+            }
+            if let Some(details) = &testcase.details {
+                children.push_str(&format!(
+                    "      <system-out>{}</system-out>\n",
+                    xml_escape(details)
+                ));
+            }
+
+            if children.is_empty() {
+                xml.push_str(&format!("    <testcase {} />\n", attributes));
+            } else {
+                xml.push_str(&format!("    <testcase {}>\n", attributes));
+                xml.push_str(&children);
+                xml.push_str("    </testcase>\n");
+            }
+        }
+
+        xml.push_str("  </testsuite>\n");
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+/// Escapes a string for use in XML text and attribute values.
+///
+/// Characters that are not allowed in XML 1.0 documents are dropped.
+pub fn xml_escape(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            _ if ch.is_control() => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// A task of a test, as written in JSON reports.
+#[derive(Serialize)]
+pub struct JsonTaskCall {
+    pub task: String,
+    pub arguments: Vec<String>,
+    pub not: bool,
+    #[serde(rename = "as")]
+    pub as_label: Option<String>,
+    pub id: Option<String>,
+}
+
+impl From<&RukiniaTaskCall> for JsonTaskCall {
+    fn from(task_call: &RukiniaTaskCall) -> Self {
+        JsonTaskCall {
+            task: task_call.task.clone(),
+            arguments: task_call.arguments.clone(),
+            not: task_call.syntax.contains_not(),
+            as_label: task_call.syntax.get_as(),
+            id: task_call.syntax.get_id(),
+        }
+    }
+}
+
+/// Details of a `RukiniaError`, as written in JSON reports.
+#[derive(Serialize)]
+pub struct JsonTestError {
+    pub command: String,
+    pub error: Option<String>,
+    pub message: String,
+}
+
+/// A single test, as written in JSON and JSON Lines reports.
+#[derive(Serialize)]
+pub struct JsonTestResult {
+    pub label: String,
+    pub result: String,
+    pub tasks: Vec<JsonTaskCall>,
+    pub not: bool,
+    #[serde(rename = "as")]
+    pub as_label: Option<String>,
+    pub id: Option<String>,
+    /// Where the test is written, as `path:line`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Execution time in seconds.
+    pub duration: f64,
+    /// Details reported by the task, e.g. the output of a plugin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// Measurements reported by the task, by name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metrics: BTreeMap<String, f64>,
+    pub error: Option<JsonTestError>,
+}
+
+impl JsonTestResult {
+    /// Builds the JSON representation of a test that produced a result.
+    pub fn from_result(entry: &RukiniaResultEntry) -> Self {
+        JsonTestResult {
+            label: entry.label.clone(),
+            result: entry.result_type.to_string(),
+            tasks: entry.tasks.iter().map(JsonTaskCall::from).collect(),
+            not: entry.syntax.contains_not(),
+            as_label: entry.syntax.get_as(),
+            id: entry.id.clone(),
+            location: entry.location.clone(),
+            duration: entry.duration.as_secs_f64(),
+            details: entry.details.clone(),
+            metrics: entry.metrics.clone(),
+            error: None,
+        }
+    }
+
+    /// Builds the JSON representation of a test that raised a `RukiniaError`.
+    pub fn from_error(error: &RukiniaError, duration: Duration) -> Self {
+        JsonTestResult {
+            label: error.label.clone(),
+            result: "SYSTEM ERROR".to_string(),
+            tasks: Vec::new(),
+            not: false,
+            as_label: None,
+            id: error.get_id().map(str::to_string),
+            location: error.get_location().map(str::to_string),
+            duration: duration.as_secs_f64(),
+            details: None,
+            metrics: BTreeMap::new(),
+            error: Some(JsonTestError {
+                command: error.test_command.clone(),
+                error: error.system_error.clone(),
+                message: error.system_error_message.clone(),
+            }),
+        }
+    }
+
+    /// Appends the test and its run as a single line to a JSON Lines file.
+    pub fn append_json_line(
+        &self,
+        file_path: &str,
+        run: &RukiniaRunInfo,
+    ) -> Result<(), Box<dyn Error>> {
+        let file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(file_path)?;
+
+        let mut file = BufWriter::new(file);
+        serde_json::to_writer(
+            &mut file,
+            &JsonLine {
+                test: self,
+                run: JsonRunInfo::from(run),
+            },
+        )?;
+        file.write_all(b"\n")?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Counters of a JSON report.
+#[derive(Serialize)]
+pub struct JsonSummary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub errors: usize,
+    /// Sum of the execution times in seconds.
+    pub duration: f64,
+}
+
+/// Identification of the run, as written in JSON and JSON Lines reports.
+#[derive(Serialize)]
+pub struct JsonRunInfo {
+    pub id: String,
+    pub start_time: String,
+    pub hostname: String,
+    pub version: String,
+}
+
+impl From<&RukiniaRunInfo> for JsonRunInfo {
+    fn from(run: &RukiniaRunInfo) -> Self {
+        JsonRunInfo {
+            id: run.run_id.clone(),
+            start_time: run.get_start_time(),
+            hostname: run.hostname.clone(),
+            version: run.version.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDocument<'a> {
+    run: Option<JsonRunInfo>,
+    summary: JsonSummary,
+    tests: &'a [JsonTestResult],
+}
+
+/// A line of a JSON Lines report: a test and the run it belongs to.
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    #[serde(flatten)]
+    test: &'a JsonTestResult,
+    run: JsonRunInfo,
+}
+
+/// JSON report buffering every test of a run into a single document.
+pub struct JsonReport {
+    pretty: bool,
+    run: Option<RukiniaRunInfo>,
+    tests: Vec<JsonTestResult>,
+}
+
+impl Default for JsonReport {
+    fn default() -> Self {
+        JsonReport::new(true)
+    }
+}
+
+impl JsonReport {
+    /// Creates an empty report, indented if `pretty` is set.
+    pub fn new(pretty: bool) -> Self {
+        JsonReport {
+            pretty,
+            run: None,
+            tests: Vec::new(),
+        }
+    }
+
+    /// Records the run the tests belong to.
+    pub fn set_run(&mut self, run: &RukiniaRunInfo) {
+        self.run = Some(run.clone());
+    }
+
+    /// Adds a test that produced a result.
+    pub fn add_result(&mut self, entry: &RukiniaResultEntry) {
+        self.tests.push(JsonTestResult::from_result(entry));
+    }
+
+    /// Adds a test that raised a `RukiniaError`.
+    pub fn add_error(&mut self, error: &RukiniaError, duration: Duration) {
+        self.tests.push(JsonTestResult::from_error(error, duration));
+    }
+
+    fn count(&self, result: &str) -> usize {
+        self.tests.iter().filter(|test| test.result == result).count()
+    }
+
+    /// Renders the whole report as a JSON document, pretty-printed if requested.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        let document = JsonDocument {
+            run: self.run.as_ref().map(JsonRunInfo::from),
+            summary: JsonSummary {
+                total: self.tests.len(),
+                passed: self.count("SUCCESS"),
+                failed: self.count("FAIL"),
+                skipped: self.count("SKIP"),
+                errors: self.count("SYSTEM ERROR"),
+                duration: self.tests.iter().map(|test| test.duration).sum(),
+            },
+            tests: &self.tests,
+        };
+        if self.pretty {
+            serde_json::to_string_pretty(&document)
+        } else {
+            serde_json::to_string(&document)
+        }
+    }
+}
+
+/// TAP directive appended to a test line.
+pub enum TapDirective {
+    Skip(String),
+    Todo(String),
+}
+
+/// Writer producing a TAP version 14 stream.
+///
+/// The version line is written with the first test and the plan line is written
+/// by `finish`, once the number of tests is known, so the stream can be consumed
+/// while tests are running.
+#[derive(Default)]
+pub struct TapWriter<W: Write> {
+    writer: W,
+    count: usize,
+    started: bool,
+}
+
+impl<W: Write> TapWriter<W> {
+    pub fn new(writer: W) -> Self {
+        TapWriter {
+            writer,
+            count: 0,
+            started: false,
+        }
+    }
+
+    fn start(&mut self) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            writeln!(self.writer, "TAP version 14")?;
+        }
+        Ok(())
+    }
+
+    /// Writes a comment line, e.g. describing the run.
+    pub fn add_comment(&mut self, comment: &str) -> io::Result<()> {
+        self.start()?;
+        writeln!(self.writer, "# {}", comment.replace(['\n', '\r'], " "))?;
+        self.writer.flush()
+    }
+
+    /// Writes the run ID, start time, hostname and rukinia version as comments.
+    pub fn add_run(&mut self, run: &RukiniaRunInfo) -> io::Result<()> {
+        self.add_comment(&format!("run_id: {}", run.run_id))?;
+        self.add_comment(&format!("start_time: {}", run.get_start_time()))?;
+        self.add_comment(&format!("hostname: {}", run.hostname))?;
+        self.add_comment(&format!("rukinia_version: {}", run.version))
+    }
+
+    /// Writes a test line, its optional directive and YAML diagnostics.
+    pub fn add_test(
+        &mut self,
+        ok: bool,
+        description: &str,
+        directive: Option<TapDirective>,
+        diagnostics: &[(&str, String)],
+    ) -> io::Result<()> {
+        self.start()?;
+        self.count += 1;
+
+        let mut line = format!(
+            "{}ok {} - {}",
+            if ok { "" } else { "not " },
+            self.count,
+            tap_escape(description)
+        );
+        match directive {
+            Some(TapDirective::Skip(reason)) => {
+                line.push_str(&format!(" # SKIP {}", tap_escape(&reason)))
+            }
+            Some(TapDirective::Todo(reason)) => {
+                line.push_str(&format!(" # TODO {}", tap_escape(&reason)))
+            }
+            None => {}
+        }
+        writeln!(self.writer, "{}", line.trim_end())?;
+
+        if !diagnostics.is_empty() {
+            writeln!(self.writer, "  ---")?;
+            for (key, value) in diagnostics {
+                writeln!(self.writer, "  {}: {}", key, value)?;
+            }
+            writeln!(self.writer, "  ...")?;
+        }
+        self.writer.flush()
+    }
+
+    /// Writes a test that produced a result.
+    pub fn add_result(&mut self, entry: &RukiniaResultEntry) -> io::Result<()> {
+        let mut diagnostics = Vec::new();
+        if let Some(id) = &entry.id {
+            diagnostics.push(("id", yaml_string(id)));
+        }
+        if let Some(location) = &entry.location {
+            diagnostics.push(("location", yaml_string(location)));
+        }
+        if let Some(details) = &entry.details {
+            diagnostics.push(("details", yaml_string(details)));
+        }
+        if !entry.metrics.is_empty() {
+            diagnostics.push((
+                "metrics",
+                serde_json::to_string(&entry.metrics).unwrap_or_default(),
+            ));
+        }
+        match entry.result_type {
+            RukiniaResultType::TestSuccess => self.add_test(true, &entry.label, None, &diagnostics),
+            RukiniaResultType::TestFail => {
+                diagnostics.push(("message", yaml_string(&entry.label)));
+                diagnostics.push(("severity", "fail".to_string()));
+                diagnostics.push(("duration_ms", entry.duration.as_millis().to_string()));
+                self.add_test(false, &entry.label, None, &diagnostics)
+            }
+            RukiniaResultType::TestSkip => self.add_test(
+                true,
+                &entry.label,
+                Some(TapDirective::Skip(
+                    entry.details.clone().unwrap_or_default(),
+                )),
+                &diagnostics,
+            ),
+        }
+    }
+
+    /// Writes a test that raised a `RukiniaError`.
+    pub fn add_error(&mut self, error: &RukiniaError, duration: Duration) -> io::Result<()> {
+        let mut diagnostics = Vec::new();
+        if let Some(id) = error.get_id() {
+            diagnostics.push(("id", yaml_string(id)));
+        }
+        if let Some(location) = error.get_location() {
+            diagnostics.push(("location", yaml_string(location)));
+        }
+        diagnostics.push(("message", yaml_string(&error.system_error_message)));
+        diagnostics.push(("severity", "error".to_string()));
+        diagnostics.push(("command", yaml_string(&error.test_command)));
+        if let Some(system_error) = &error.system_error {
+            diagnostics.push(("error", yaml_string(system_error)));
+        }
+        diagnostics.push(("duration_ms", duration.as_millis().to_string()));
+        self.add_test(false, &error.test_command, None, &diagnostics)
+    }
+
+    /// Writes the plan line closing the stream.
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.start()?;
+        writeln!(self.writer, "1..{}", self.count)?;
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Escapes a TAP test description, which must hold on one line and must not
+/// contain an unescaped `#`.
+fn tap_escape(input: &str) -> String {
+    input
+        .replace('\\', "\\\\")
+        .replace('#', "\\#")
+        .replace(['\n', '\r'], " ")
+}
+
+/// Quotes a string for a YAML diagnostics block; a JSON string is a valid YAML scalar.
+fn yaml_string(input: &str) -> String {
+    serde_json::to_string(input).unwrap_or_default()
+}
+
+/// Result of a LAVA test case.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LavaResult {
+    Pass,
+    Fail,
+    Skip,
+    Unknown,
+}
+
+impl fmt::Display for LavaResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LavaResult::Pass => write!(f, "pass"),
+            LavaResult::Fail => write!(f, "fail"),
+            LavaResult::Skip => write!(f, "skip"),
+            LavaResult::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl From<&RukiniaResultType> for LavaResult {
+    fn from(result_type: &RukiniaResultType) -> Self {
+        match result_type {
+            RukiniaResultType::TestSuccess => LavaResult::Pass,
+            RukiniaResultType::TestFail => LavaResult::Fail,
+            RukiniaResultType::TestSkip => LavaResult::Skip,
+        }
+    }
+}
+
+/// Writer producing the signals parsed by LAVA on the serial console.
+#[derive(Default)]
+pub struct LavaWriter<W: Write> {
+    writer: W,
+    test_case_ids: HashMap<String, usize>,
+}
+
+impl<W: Write> LavaWriter<W> {
+    pub fn new(writer: W) -> Self {
+        LavaWriter {
+            writer,
+            test_case_ids: HashMap::new(),
+        }
+    }
+
+    /// Turns `name` into a valid LAVA test case ID, unique within the run.
+    ///
+    /// The name is sanitized with `lava_sanitize`, and `_2`, `_3`... is appended
+    /// to names that were already used.
+    pub fn test_case_id(&mut self, name: &str) -> String {
+        let sanitized = lava_sanitize(name);
+
+        let count = self.test_case_ids.entry(sanitized.clone()).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            sanitized
+        } else {
+            format!("{}_{}", sanitized, count)
+        }
+    }
+
+    /// Writes the signal opening a test set.
+    pub fn start_test_set(&mut self, name: &str) -> io::Result<()> {
+        writeln!(self.writer, "<LAVA_SIGNAL_TESTSET START {}>", name)?;
+        self.writer.flush()
+    }
+
+    /// Writes the signal closing the current test set.
+    pub fn stop_test_set(&mut self) -> io::Result<()> {
+        writeln!(self.writer, "<LAVA_SIGNAL_TESTSET STOP>")?;
+        self.writer.flush()
+    }
+
+    /// Writes the signal sent before a test case runs.
+    pub fn start_test_case(&mut self, test_case_id: &str) -> io::Result<()> {
+        writeln!(self.writer, "<LAVA_SIGNAL_STARTTC {}>", test_case_id)?;
+        self.writer.flush()
+    }
+
+    /// Writes the signals sent once a test case ran: its end and its result.
+    pub fn end_test_case(
+        &mut self,
+        test_case_id: &str,
+        result: LavaResult,
+        duration: Duration,
+    ) -> io::Result<()> {
+        writeln!(self.writer, "<LAVA_SIGNAL_ENDTC {}>", test_case_id)?;
+        writeln!(
+            self.writer,
+            "<LAVA_SIGNAL_TESTCASE TEST_CASE_ID={} RESULT={} MEASUREMENT={} UNITS=seconds>",
+            test_case_id,
+            result,
+            format_duration(duration)
+        )?;
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Turns `name` into a valid LAVA test case or test set name.
+///
+/// Letters, digits, `-`, `_` and `.` are kept as written. Each run of other
+/// characters is replaced by a single `_`, or dropped at the start and end of the
+/// name, and an empty name becomes `test`.
+pub fn lava_sanitize(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len());
+    let mut replaced = false;
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+            if replaced && !sanitized.is_empty() {
+                sanitized.push('_');
+            }
+            replaced = false;
+            sanitized.push(ch);
+        } else {
+            replaced = true;
+        }
+    }
+
+    if sanitized.is_empty() {
+        "test".to_string()
+    } else {
+        sanitized
+    }
+}
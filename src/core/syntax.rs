@@ -1,6 +1,6 @@
-use super::rukinia_result::RukiniaError;
+use std::fmt;
 
-#[derive(Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Syntax {
     Not,
     As(String),
@@ -27,30 +27,51 @@ impl Syntax {
             _ => None,
         }
     }
+}
 
-    pub async fn find_syntax_as(expression: Vec<String>) -> Option<Self> {
-        let mut iter = expression.iter();
+impl fmt::Display for Syntax {
+    /// Formats the modifier as written in a test plan, quoting its argument.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Syntax::Not => write!(f, "{}", Syntax::NOT),
+            Syntax::As(label) => write!(f, "{} {:?}", Syntax::AS, label),
+            Syntax::Id(id) => write!(f, "{} {:?}", Syntax::ID, id),
+        }
+    }
+}
 
-        while let Some(word) = iter.next() {
-            match word.as_str() {
-                Syntax::AS => {
-                    if let Some(argument) = iter.next() {
-                        return Some(Syntax::As(argument.clone()));
-                    } else {
-                        eprintln!("Error: Missing argument for 'as'");
-                        return None;
-                    }
-                }
-                _ => {
-                    continue;
-                }
-            }
+/// Condition prefixing a test, deciding whether it is run or skipped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ConditionKind {
+    /// `skip_if (<condition>)`: the test is skipped if the condition passes.
+    SkipIf,
+    /// `only_if (<condition>)`: the test is skipped unless the condition passes.
+    OnlyIf,
+}
+
+impl ConditionKind {
+    pub const SKIP_IF: &'static str = "skip_if";
+    pub const ONLY_IF: &'static str = "only_if";
+
+    /// Returns true if the test must be skipped, given whether its condition passed.
+    pub fn skips(&self, passed: bool) -> bool {
+        match self {
+            ConditionKind::SkipIf => passed,
+            ConditionKind::OnlyIf => !passed,
         }
-        None
     }
 }
 
-#[derive(Clone)]
+impl fmt::Display for ConditionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConditionKind::SkipIf => write!(f, "{}", ConditionKind::SKIP_IF),
+            ConditionKind::OnlyIf => write!(f, "{}", ConditionKind::ONLY_IF),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum SyntaxForTrait {
     Arguments(Vec<Syntax>),
 }
@@ -71,49 +92,19 @@ impl SyntaxForTrait {
         }
     }
 
-    pub async fn extract_syntax(
-        expression: Vec<String>,
-    ) -> Result<(Self, Vec<String>), RukiniaError> {
-        let mut syntax = Vec::new();
-        let mut iter = expression.iter();
+    /// Returns the modifiers as written in a test plan, each followed by a space.
+    pub fn get_prefix(&self) -> String {
+        match self {
+            SyntaxForTrait::Arguments(vector) => vector.iter().map(|s| format!("{} ", s)).collect(),
+        }
+    }
 
-        while let Some(word) = iter.next() {
-            match word.as_str() {
-                Syntax::NOT => {
-                    syntax.push(Syntax::Not);
-                }
-                Syntax::AS => match iter.next() {
-                    Some(argument) => {
-                        syntax.push(Syntax::As(argument.clone()));
-                    }
-                    None => {
-                        return Err(RukiniaError::new(
-                            "AS label error".to_string(),
-                            "Missing argument for AS".to_string(),
-                            "No argument found".to_string(),
-                        ));
-                    }
-                },
-                Syntax::ID => match iter.next() {
-                    Some(argument) => {
-                        syntax.push(Syntax::Id(argument.clone()));
-                    }
-                    None => {
-                        return Err(RukiniaError::new(
-                            "ID label error".to_string(),
-                            "Missing argument for ID".to_string(),
-                            "No argument found".to_string(),
-                        ));
-                    }
-                },
-                _ => {
-                    let mut remaining = Vec::new();
-                    remaining.push(word.clone());
-                    remaining.extend(iter.cloned());
-                    return Ok((SyntaxForTrait::Arguments(syntax), remaining));
-                }
-            }
+    pub fn get_id(&self) -> Option<String> {
+        match self {
+            SyntaxForTrait::Arguments(vector) => vector
+                .iter()
+                .find(|s| matches!(s, Syntax::Id(_)))
+                .and_then(|s| s.get_argument()),
         }
-        Ok((SyntaxForTrait::Arguments(syntax), Vec::new()))
     }
 }
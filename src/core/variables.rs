@@ -0,0 +1,142 @@
+//! Rukinia Test Plan Variables
+//!
+//! Test plans define variables with `set NAME value` and use them as `${NAME}` in the
+//! arguments of tasks and in `as` labels; `${env:NAME}` reads an environment variable
+//! instead. `$$` stands for a single `$`, so `$${NAME}` is left untouched.
+//!
+//! ```text
+//! set IFACE eth0
+//! as "${IFACE} is up" rukinia_netif_is_up ${IFACE}
+//! rukinia_user ${env:TEST_USER}
+//! ```
+//!
+//! Variables defined on the command line with `-D NAME=value` take precedence over
+//! those set by the test plans, so that one plan can serve several boards.
+
+use std::collections::BTreeMap;
+use std::env;
+
+/// Keyword of the statement setting a variable.
+pub const SET_KEYWORD: &str = "set";
+
+/// Prefix of the variables read from the environment, e.g. `${env:HOME}`.
+pub const ENV_PREFIX: &str = "env:";
+
+/// Variables available to the tests of a test plan.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct RukiniaVariables {
+    /// Variables defined on the command line, never overridden by the test plans.
+    defines: BTreeMap<String, String>,
+    /// Variables set by the test plan.
+    values: BTreeMap<String, String>,
+}
+
+impl RukiniaVariables {
+    /// Creates the variables defined on the command line.
+    pub fn new(defines: Vec<(String, String)>) -> Self {
+        RukiniaVariables {
+            defines: defines.into_iter().collect(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the value of a variable.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.defines
+            .get(name)
+            .or_else(|| self.values.get(name))
+            .map(String::as_str)
+    }
+
+    /// Sets a variable to `value` after expanding it, unless the variable is defined on
+    /// the command line.
+    ///
+    /// # Errors
+    /// Returns a description of the problem if the name is invalid or if `value` uses an
+    /// undefined variable.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        if !is_variable_name(name) {
+            return Err(format!("Invalid variable name '{}'", name));
+        }
+        let value = self.expand(value)?;
+        if !self.defines.contains_key(name) {
+            self.values.insert(name.to_string(), value);
+        }
+        Ok(())
+    }
+
+    /// Replaces every `${NAME}` and `${env:NAME}` of `text` by its value.
+    ///
+    /// # Errors
+    /// Returns a description of the problem if a variable is undefined or if a `${` is
+    /// not closed.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let variables = RukiniaVariables::new(vec![("IFACE".to_string(), "eth0".to_string())]);
+    /// assert_eq!(variables.expand("${IFACE} costs $$5")?, "eth0 costs $5");
+    /// ```
+    pub fn expand(&self, text: &str) -> Result<String, String> {
+        let mut expanded = String::new();
+        let mut rest = text;
+
+        while let Some(index) = rest.find('$') {
+            expanded.push_str(&rest[..index]);
+            rest = &rest[index..];
+
+            if let Some(after) = rest.strip_prefix("$$") {
+                expanded.push('$');
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("${") {
+                let end = after
+                    .find('}')
+                    .ok_or_else(|| format!("Missing '}}' to close '${{' in '{}'", text))?;
+                expanded.push_str(&self.lookup(&after[..end])?);
+                rest = &after[end + 1..];
+            } else {
+                expanded.push('$');
+                rest = &rest[1..];
+            }
+        }
+        expanded.push_str(rest);
+
+        Ok(expanded)
+    }
+
+    fn lookup(&self, name: &str) -> Result<String, String> {
+        match name.strip_prefix(ENV_PREFIX) {
+            Some(name) => {
+                env::var(name).map_err(|_| format!("Undefined environment variable '{}'", name))
+            }
+            None => self
+                .get(name)
+                .map(str::to_string)
+                .ok_or_else(|| format!("Undefined variable '{}'", name)),
+        }
+    }
+}
+
+/// Returns true if `name` can name a variable: letters, digits and `_`, not starting
+/// with a digit.
+pub fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// Parses a variable defined on the command line as `NAME=value`.
+///
+/// # Errors
+/// Returns a description of the problem if there is no `=` or the name is invalid.
+pub fn rukinia_parse_define(define: &str) -> Result<(String, String), String> {
+    match define.split_once('=') {
+        Some((name, value)) if is_variable_name(name) => Ok((name.to_string(), value.to_string())),
+        Some((name, _)) => Err(format!("invalid variable name '{}'", name)),
+        None => Err(format!(
+            "invalid definition '{}', expected NAME=VALUE",
+            define
+        )),
+    }
+}
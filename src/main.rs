@@ -6,41 +6,235 @@
 //! # Features
 //! - Interactive shell mode for manual command execution
 //! - Configurable test execution
-//! - Multiple output formats for test results (CSV, Text, JUnit)
+//! - Multiple output formats for test results (CSV, Text, JUnit, JSON, JSON Lines, TAP)
 //! - Performance timing
 //!
 //! # Usage
-//! See the `print_help()` function for command-line options or run with `--help`
+//! See the `Cli` structure for command-line options or run with `--help`
 
-mod core;
-mod tasks;
-
-use core::save_test_result::ResultFormat;
-use std::env;
-use std::error::Error;
+use std::process::ExitCode;
+use std::str::FromStr;
 use std::time::Instant;
 
-use crate::core::configuration::rukinia_use_settings;
-use crate::core::interactive_shell::interactive_shell;
-use crate::core::run_tasks::rukinia_run_analysis;
-
-use crate::core::save_test_result::FormatOutput;
-
-/// Prints help information about command-line options
-fn print_help() {
-    println!("Usage: Rukinia [OPTIONS]");
-    println!("\nOptions:");
-    println!("  help      Show this help message and exit");
-    println!("  config    Specify a configuration file (default: config)");
-    println!("  shell     Shell to manually enter rukinia commands");
-    println!("  save-csv  Save test result in a CSV file");
-    println!("  custom-path-csv  File path of the output file for CSV");
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+
+use rukinia::core::configuration::{
+    rukinia_load_settings, rukinia_load_test_plans, rukinia_set_tasks_settings,
+    rukinia_use_settings, RukiniaConfig, RukiniaSettingsSources,
+};
+use rukinia::core::interactive_shell::interactive_shell;
+use rukinia::core::plan::rukinia_check_blocks;
+use rukinia::core::reporter::rukinia_reporters;
+use rukinia::core::rukinia_result::{set_color_enabled, ColorMode, FailOn, RukiniaExitCode};
+use rukinia::core::run_tasks::{
+    rukinia_check_result_formats, rukinia_check_test_ids, rukinia_check_test_plans,
+    rukinia_describe_test_plans, rukinia_run_analysis, TestSelection,
+};
+use rukinia::core::save_test_result::{
+    ConsoleOutput, FormatOutput, ResultFormat, ResultFormatOptions,
+};
+use rukinia::core::variables::{rukinia_parse_define, RukiniaVariables};
+#[cfg(feature = "plugin")]
+use rukinia::tasks::plugin::rukinia_plugin::rukinia_register_plugins;
+use rukinia::tasks::task::RukiniaAllTasks;
+
+const EXIT_CODES_HELP: &str = "Exit codes:
+  0  All tests passed
+  1  Some tests failed
+  2  Some tests raised a system error
+  3  The settings, the command line or the test plan are invalid";
+
+/// Small tester for embedded linux system
+///
+/// Runs the `run` command when no command is given.
+#[derive(Parser)]
+#[command(name = "rukinia", version, after_help = EXIT_CODES_HELP)]
+struct Cli {
+    /// Settings file, read after /etc/rukinia/config.toml and $XDG_CONFIG_HOME/rukinia/config.toml
+    #[arg(long, global = true, value_name = "PATH")]
+    settings: Option<String>,
+
+    /// Colour the console output [default: from the settings, auto]
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODE",
+        value_parser = possible_values::<ColorMode>(&["auto", "always", "never"])
+    )]
+    color: Option<ColorMode>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the tests of the test plans
+    Run(RunArgs),
+    /// Shell to manually enter rukinia tests
+    Shell,
+    /// Check the test plans without running them
+    Check(PlanArgs),
+    /// List the tasks available in test plans with their arguments
+    ListTasks,
+}
+
+#[derive(Args)]
+struct PlanArgs {
+    /// Test plan file, directory of *.conf files or - for stdin [default: from the settings,
+    /// /etc/rukinia/rukinia.conf]
+    #[arg(value_name = "PLAN")]
+    plans: Vec<String>,
+
+    /// Define the variable NAME used as ${NAME} in the test plans, overriding their `set`
+    /// statements. Can be repeated
+    #[arg(
+        short = 'D',
+        long = "define",
+        value_name = "NAME=VALUE",
+        value_parser = rukinia_parse_define
+    )]
+    defines: Vec<(String, String)>,
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[command(flatten)]
+    plan: PlanArgs,
+
+    /// Write a report; FORMAT is csv, text, junit, json, jsonl or tap, PATH defaults to the
+    /// output path of the settings with the extension of the format, OPTION is
+    /// mode=truncate|append|timestamped, suite_name=NAME (junit) or pretty=false (json).
+    /// Can be repeated
+    #[arg(
+        short,
+        long,
+        value_name = "FORMAT[=PATH][,OPTION]...",
+        value_parser = parse_output
+    )]
+    output: Vec<ResultFormatArg>,
+
+    /// Print results on stdout [default: from the settings, text]
+    #[arg(
+        short,
+        long,
+        value_name = "FORMAT",
+        value_parser = possible_values::<ConsoleOutput>(&["text", "tap", "lava"])
+    )]
+    console: Option<ConsoleOutput>,
+
+    /// Exit with a non-zero code on failed tests and errors, on errors only, or never
+    #[arg(
+        long,
+        value_name = "POLICY",
+        default_value = "fail",
+        value_parser = possible_values::<FailOn>(&["fail", "error", "never"])
+    )]
+    fail_on: FailOn,
+
+    /// Only run the test with this id. Can be repeated
+    #[arg(short, long, value_name = "ID")]
+    id: Vec<String>,
+
+    /// Do not run the test with this id. Can be repeated
+    #[arg(short = 'x', long, value_name = "ID")]
+    exclude_id: Vec<String>,
+
+    /// Print the task, arguments, modifiers and expected label of each test instead of
+    /// running it; no report is written
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+/// Parses the command line.
+///
+/// The options of `run` given before another command are refused rather than
+/// ignored, the global options being accepted before any command.
+fn parse_command_line() -> Result<Cli, clap::Error> {
+    let mut cli = Cli::command();
+    let matches = cli.try_get_matches_from_mut(std::env::args_os())?;
+    if let Some((command, _)) = matches.subcommand() {
+        let run_arg = cli.get_arguments().find(|arg| {
+            !arg.is_global_set()
+                && matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+        });
+        if let Some(arg) = run_arg {
+            return Err(cli.error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "the argument '{}' cannot be used with the '{}' command",
+                    arg, command
+                ),
+            ));
+        }
+    }
+    Cli::from_arg_matches(&matches)
+}
+
+/// A report requested with `--output FORMAT[=PATH][,OPTION]...`.
+#[derive(Clone)]
+struct ResultFormatArg {
+    format: FormatOutput,
+    path: Option<String>,
+    options: ResultFormatOptions,
+}
+
+/// Parser accepting the `values` of a type implementing `FromStr`, listed in the help.
+fn possible_values<T>(values: &'static [&'static str]) -> impl TypedValueParser<Value = T>
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+{
+    PossibleValuesParser::new(values)
+        .try_map(|value| T::from_str(&value).map_err(|_| format!("invalid value '{}'", value)))
+}
+
+fn parse_output(value: &str) -> Result<ResultFormatArg, String> {
+    let mut parts = value.split(',');
+    let value = parts.next().unwrap_or_default();
+    let (format, path) = match value.split_once('=') {
+        Some((format, path)) => (format, Some(path.to_string())),
+        None => (value, None),
+    };
+    let format = FormatOutput::from_str(format).map_err(|_| {
+        format!(
+            "invalid format '{}', expected csv, text, junit, json, jsonl or tap",
+            format
+        )
+    })?;
+    if path.as_deref() == Some("") {
+        return Err("empty report path".to_string());
+    }
+
+    let mut options = ResultFormatOptions::default();
+    for option in parts {
+        match option.split_once('=') {
+            Some((key, value)) => options.set(key, value)?,
+            None => {
+                return Err(format!(
+                    "invalid report option '{}', expected KEY=VALUE",
+                    option
+                ))
+            }
+        }
+    }
+
+    Ok(ResultFormatArg {
+        format,
+        path,
+        options,
+    })
 }
 
 /// Main entry point for Rukinia application
 ///
 /// # Returns
-/// Returns `Result<(), Box<dyn Error>>` indicating success or failure of the application
+/// Returns an `ExitCode` reflecting the outcome of the tests, see `RukiniaExitCode`
 ///
 /// # Examples
 /// Basic usage:
@@ -49,60 +243,218 @@ fn print_help() {
 /// // rukinia --help
 /// ```
 ///
-/// Running tests and saving CSV results:
+/// Running the tests of a directory of test plans:
+/// ```
+/// // rukinia run /etc/rukinia/rukinia.d
+/// ```
+///
+/// Running tests and saving JUnit and CSV results:
+/// ```
+/// // rukinia run --output junit=report.xml --output csv=results.csv
+/// ```
+///
+/// Reviewing what a test plan checks without running it:
+/// ```
+/// // rukinia run --dry-run rukinia.conf
+/// ```
+///
+/// Only failing the CI job on system errors:
 /// ```
-/// // rukinia --save-csv --custom-path-csv my_results.csv
+/// // rukinia run --fail-on error
 /// ```
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() -> ExitCode {
+    let cli = match parse_command_line() {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return if e.use_stderr() {
+                RukiniaExitCode::ConfigurationError.into()
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
+
+    // Colour the errors of the settings as asked on the command line
+    set_color_enabled(cli.color.unwrap_or_default().is_enabled());
+    let settings = match rukinia_load_settings(&RukiniaSettingsSources::from_process(
+        cli.settings.as_deref(),
+    )) {
+        Ok(settings) => settings,
+        Err(e) => {
+            e.display_result();
+            return RukiniaExitCode::ConfigurationError.into();
+        }
+    };
+    if let (None, Some(color)) = (cli.color, settings.output.color) {
+        set_color_enabled(color.is_enabled());
+    }
+    rukinia_set_tasks_settings(settings.tasks.clone());
+    #[cfg(feature = "plugin")]
+    rukinia_register_plugins(&settings.tasks.plugins.paths);
+
+    match cli.command.unwrap_or(Command::Run(cli.run)) {
+        Command::Run(args) => run(args, &settings),
+        Command::Shell => {
+            interactive_shell();
+            ExitCode::SUCCESS
+        }
+        Command::Check(args) => check(args, &settings),
+        Command::ListTasks => {
+            for task in RukiniaAllTasks::all() {
+                let entry = task.get_entry();
+                println!(
+                    "{}",
+                    format!("{} {}", entry.name, entry.synopsis).trim_end()
+                );
+                match &entry.feature {
+                    Some(feature) => println!("    {} (feature {})", entry.description, feature),
+                    None => println!("    {}", entry.description),
+                }
+            }
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// Returns the test plans given on the command line, those of the settings otherwise.
+fn test_plan_paths(args: &PlanArgs, settings: &RukiniaConfig) -> Vec<String> {
+    if args.plans.is_empty() {
+        settings.plan.paths.clone()
+    } else {
+        args.plans.clone()
+    }
+}
+
+/// Runs the tests of the test plans and prints the summary.
+fn run(args: RunArgs, settings: &RukiniaConfig) -> ExitCode {
+    if args.dry_run {
+        return dry_run(args, settings);
+    }
+
     let start_time = Instant::now();
-    let args: Vec<String> = env::args().collect();
-    let mut result_format: Option<ResultFormat> = None;
 
-    if args.contains(&"help".to_string()) {
-        print_help();
-        return Ok(());
+    let console_output = args.console.unwrap_or(settings.output.console);
+    let selection = TestSelection {
+        only: args.id,
+        exclude: args.exclude_id,
+    };
+
+    let mut result_formats: Vec<ResultFormat> = args
+        .output
+        .into_iter()
+        .map(|output| ResultFormat {
+            path: output
+                .path
+                .unwrap_or_else(|| settings.output.get_report_path(&output.format)),
+            format: output.format,
+            options: output.options,
+        })
+        .collect();
+    if result_formats.is_empty() {
+        result_formats = settings.output.get_reports();
+    }
+    if let Err(e) = rukinia_check_result_formats(&result_formats) {
+        e.display_result();
+        return RukiniaExitCode::ConfigurationError.into();
+    }
+    let mut reporters = rukinia_reporters(console_output, &result_formats);
+
+    let test_plan_paths = test_plan_paths(&args.plan, settings);
+    let variables = RukiniaVariables::new(args.plan.defines);
+
+    let outcome = match rukinia_use_settings(settings) {
+        Ok(run) => run.block_on(rukinia_run_analysis(
+            &test_plan_paths,
+            &mut reporters,
+            &selection,
+            &variables,
+        )),
+        Err(e) => {
+            eprintln!("Failed to create runtime: {}", e);
+            return RukiniaExitCode::ConfigurationError.into();
+        }
+    };
+
+    match outcome {
+        Ok(outcome) => {
+            if console_output == ConsoleOutput::Text {
+                let elapsed_time = start_time.elapsed();
+                println!("Time spent: {:.3}", elapsed_time.as_secs_f64());
+            }
+            outcome.exit_code(args.fail_on).into()
+        }
+        Err(e) => {
+            e.display_result();
+            RukiniaExitCode::ConfigurationError.into()
+        }
     }
+}
 
-    if args.contains(&"shell".to_string()) {
-        interactive_shell();
-        return Ok(());
+/// Prints what each selected test would check, without running it.
+fn dry_run(args: RunArgs, settings: &RukiniaConfig) -> ExitCode {
+    let selection = TestSelection {
+        only: args.id,
+        exclude: args.exclude_id,
+    };
+
+    let plans = match rukinia_load_test_plans(&test_plan_paths(&args.plan, settings)) {
+        Ok(plans) => plans,
+        Err(e) => {
+            e.display_result();
+            return RukiniaExitCode::ConfigurationError.into();
+        }
+    };
+    for plan in &plans {
+        if let Err(e) = rukinia_check_blocks(&plan.lines) {
+            e.display_result();
+            return RukiniaExitCode::ConfigurationError.into();
+        }
+    }
+    if let Err(e) = rukinia_check_test_ids(&plans, &selection) {
+        e.display_result();
+        return RukiniaExitCode::ConfigurationError.into();
     }
 
-    let runtime = rukinia_use_settings();
+    let variables = RukiniaVariables::new(args.plan.defines);
+    let descriptions = rukinia_describe_test_plans(&plans, &selection, &variables);
+    for description in &descriptions {
+        println!("{}", description);
+    }
 
-    let custom_path = args
+    if descriptions
         .iter()
-        .position(|arg| arg == "custom-path")
-        .and_then(|index| args.get(index + 1).cloned())
-        .unwrap_or_else(|| "rukinia".to_string());
-
-    if args.contains(&"save-csv".to_string()) {
-        result_format = Some(ResultFormat {
-            format: FormatOutput::Csv,
-            path: custom_path,
-        });
-    } else if args.contains(&"save-text".to_string()) {
-        result_format = Some(ResultFormat {
-            format: FormatOutput::TextFile,
-            path: custom_path,
-        });
-    } else if args.contains(&"save-junit".to_string()) {
-        result_format = Some(ResultFormat {
-            format: FormatOutput::JUnit,
-            path: custom_path,
-        });
+        .any(|description| description.error.is_some())
+    {
+        RukiniaExitCode::ConfigurationError.into()
+    } else {
+        ExitCode::SUCCESS
     }
+}
 
-    match runtime {
-        Ok(run) => run.block_on(rukinia_run_analysis(result_format)),
+/// Checks the test plans and prints the problems found.
+fn check(args: PlanArgs, settings: &RukiniaConfig) -> ExitCode {
+    let plans = match rukinia_load_test_plans(&test_plan_paths(&args, settings)) {
+        Ok(plans) => plans,
         Err(e) => {
-            eprintln!("Failed to create runtime: {}", e);
-            return Ok(());
+            e.display_result();
+            return RukiniaExitCode::ConfigurationError.into();
         }
     };
 
-    let elapsed_time = start_time.elapsed();
-    println!("Time spent: {:.3}", elapsed_time.as_secs_f64());
+    let problems = rukinia_check_test_plans(&plans, &RukiniaVariables::new(args.defines));
+    for problem in &problems {
+        println!("{}\n", problem);
+    }
+    println!(
+        "{} test plan(s) checked, {} problem(s) found",
+        plans.len(),
+        problems.len()
+    );
 
-    Ok(())
+    if problems.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        RukiniaExitCode::ConfigurationError.into()
+    }
 }
@@ -1,6 +1,6 @@
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, RukiniaProcess};
 
 pub struct RukiniaFalse {
     pub syntax: SyntaxForTrait,
@@ -12,6 +12,14 @@ impl RukiniaProcess for RukiniaFalse {
         "false"
     }
 
+    fn get_description() -> &'static str {
+        "Always fails"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 0, Some(0), Self::get_synopsis())
+    }
+
     fn set_result(&mut self, result: RukiniaResultEntry) {
         self.result = result;
     }
@@ -20,16 +28,20 @@ impl RukiniaProcess for RukiniaFalse {
         self.syntax.clone()
     }
 
-    fn new(_arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
     where
         Self: Sized,
     {
-        let mut rukinia_false = RukiniaFalse {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaFalse {
             syntax,
             result: RukiniaResultEntry::new(RukiniaResultType::TestFail, "false".to_string()),
-        };
-        rukinia_false.apply_syntax();
-        Ok(rukinia_false)
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        self.apply_syntax();
+        Ok(())
     }
 
     fn get_result(&self) -> RukiniaResultEntry {
@@ -1,6 +1,6 @@
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, RukiniaProcess};
 
 pub struct RukiniaTrue {
     pub syntax: SyntaxForTrait,
@@ -12,16 +12,28 @@ impl RukiniaProcess for RukiniaTrue {
         "true"
     }
 
-    fn new(_arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    fn get_description() -> &'static str {
+        "Always succeeds"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 0, Some(0), Self::get_synopsis())
+    }
+
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
     where
         Self: Sized,
     {
-        let mut rukinia_true = RukiniaTrue {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaTrue {
             syntax,
             result: RukiniaResultEntry::new(RukiniaResultType::TestSuccess, "true".to_string()),
-        };
-        rukinia_true.apply_syntax();
-        Ok(rukinia_true)
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        self.apply_syntax();
+        Ok(())
     }
 
     fn get_result(&self) -> RukiniaResultEntry {
@@ -4,7 +4,7 @@
 //! process within the Rukinia framework.
 //!
 //! # Overview
-//! `RukiniaCmd` executes external commands, captures their output, and applies syntax rules.
+//! `RukiniaCmd` executes external commands when run, captures their output, and applies syntax rules.
 //! It implements the `RukiniaProcess` trait.
 //!
 //! # Errors
@@ -12,57 +12,59 @@
 
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
 use std::io;
 use std::process::{Command, Output};
 
 /// Represents a Rukinia command execution process.
 ///
 /// This struct stores the command arguments, syntax rules, and execution result.
+#[derive(Clone)]
 pub struct RukiniaCmd {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
     result: RukiniaResultEntry,
 }
 
+impl RukiniaCmd {
+    /// Runs the command, the test succeeds if it exits successfully.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
+        let command = &self.arguments[0];
+        let command_args = &self.arguments[1..];
+
+        let output: io::Result<Output> = Command::new(command).args(command_args).output();
+
+        if output.is_ok() && output.unwrap().status.success() {
+            self.result.result_type = RukiniaResultType::TestSuccess;
+        }
+
+        Ok(())
+    }
+}
+
 impl RukiniaProcess for RukiniaCmd {
-    /// Creates a new `RukiniaCmd` instance.
+    /// Creates a new `RukiniaCmd` instance, without running the command.
     ///
     /// # Arguments
     /// * `arguments` - A vector of strings representing the command and its arguments.
     /// * `syntax` - The syntax rules applied to the command execution.
     ///
     /// # Returns
-    /// Returns `Ok(RukiniaCmd)` if the command executes successfully, otherwise returns a `RukiniaError`.
+    /// Returns `Ok(RukiniaCmd)` if a command is given, otherwise returns a `RukiniaError`.
     fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
     where
         Self: Sized,
     {
-        if arguments.is_empty() {
-            return Err(RukiniaError::new(
-                "rukinia_cmd".to_string(),
-                "Missing command argument".to_string(),
-                "No command provided".to_string(),
-            ));
-        }
-
-        let mut rukinia_cmd = RukiniaCmd {
-            arguments: arguments.clone(),
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaCmd {
+            arguments,
             syntax,
             result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
-
-        let command = arguments[0].clone();
-        let command_args = &arguments[1..];
-
-        let output: io::Result<Output> = Command::new(command).args(command_args).output();
-
-        if output.is_ok() && output.unwrap().status.success() {
-            rukinia_cmd.result.result_type = RukiniaResultType::TestSuccess;
-        }
+        })
+    }
 
-        rukinia_cmd.apply_syntax();
-        Ok(rukinia_cmd)
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
     }
 
     /// Returns the name of the Rukinia command.
@@ -70,6 +72,21 @@ impl RukiniaProcess for RukiniaCmd {
         "rukinia_cmd"
     }
 
+    /// Returns the description shown by `rukinia list-tasks`.
+    fn get_description() -> &'static str {
+        "Runs a command, succeeds if it exits successfully"
+    }
+
+    /// Returns the arguments expected by the command.
+    fn get_synopsis() -> &'static str {
+        "<command> [argument...]"
+    }
+
+    /// Checks a command to run is given.
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 1, None, Self::get_synopsis())
+    }
+
     /// Retrieves the result of the command execution.
     fn get_result(&self) -> RukiniaResultEntry {
         self.result.clone()
@@ -2,62 +2,23 @@ use std::fs;
 
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
 use std::path::Path;
+#[derive(Clone)]
 pub struct RukiniaSymlink {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
     result: RukiniaResultEntry,
 }
 
-impl RukiniaProcess for RukiniaSymlink {
-    fn get_rukinia_command() -> &'static str {
-        "rukinia_symlink"
-    }
-
-    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
-    where
-        Self: Sized,
-    {
-        let mut rukinia_symlink = RukiniaSymlink {
-            arguments,
-            syntax,
-            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
-
-        let link_path = match rukinia_symlink.arguments.first() {
-            Some(path) => Path::new(path),
-            None => {
-                return Err(RukiniaError::new(
-                    format!(
-                        "{} {}",
-                        RukiniaSymlink::get_rukinia_command(),
-                        rukinia_symlink.arguments.join(" ")
-                    ),
-                    "Missing link path argument".to_string(),
-                    "No link path provided".to_string(),
-                ));
-            }
-        };
-
-        let target_path = match rukinia_symlink.arguments.get(1) {
-            Some(path) => Path::new(path),
-            None => {
-                return Err(RukiniaError::new(
-                    format!(
-                        "{} {}",
-                        RukiniaSymlink::get_rukinia_command(),
-                        rukinia_symlink.arguments.join(" ")
-                    ),
-                    "Missing target path argument".to_string(),
-                    "No target path provided".to_string(),
-                ));
-            }
-        };
+impl RukiniaSymlink {
+    /// Compares the canonical target of the link with the expected one.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
+        let link_path = Path::new(&self.arguments[0]);
+        let target_path = Path::new(&self.arguments[1]);
 
         if !link_path.is_symlink() {
-            rukinia_symlink.apply_syntax();
-            return Ok(rukinia_symlink);
+            return Ok(());
         }
 
         let link_target = match fs::read_link(link_path) {
@@ -67,7 +28,7 @@ impl RukiniaProcess for RukiniaSymlink {
                     format!(
                         "{} {}",
                         RukiniaSymlink::get_rukinia_command(),
-                        rukinia_symlink.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "Failed to read symbolic link".to_string(),
                     err.to_string(),
@@ -82,7 +43,7 @@ impl RukiniaProcess for RukiniaSymlink {
                     format!(
                         "{} {}",
                         RukiniaSymlink::get_rukinia_command(),
-                        rukinia_symlink.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "Failed to canonicalize link target".to_string(),
                     err.to_string(),
@@ -97,7 +58,7 @@ impl RukiniaProcess for RukiniaSymlink {
                     format!(
                         "{} {}",
                         RukiniaSymlink::get_rukinia_command(),
-                        rukinia_symlink.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "Failed to canonicalize target target".to_string(),
                     err.to_string(),
@@ -106,11 +67,44 @@ impl RukiniaProcess for RukiniaSymlink {
         };
 
         if canonical_link_target == canonical_target {
-            rukinia_symlink.result.result_type = RukiniaResultType::TestSuccess;
+            self.result.result_type = RukiniaResultType::TestSuccess;
         }
 
-        rukinia_symlink.apply_syntax();
-        Ok(rukinia_symlink)
+        Ok(())
+    }
+}
+
+impl RukiniaProcess for RukiniaSymlink {
+    fn get_rukinia_command() -> &'static str {
+        "rukinia_symlink"
+    }
+
+    fn get_description() -> &'static str {
+        "Checks a symbolic link points to a target"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<link> <target>"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 2, Some(2), Self::get_synopsis())
+    }
+
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    where
+        Self: Sized,
+    {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaSymlink {
+            arguments,
+            syntax,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
     }
 
     fn get_result(&self) -> RukiniaResultEntry {
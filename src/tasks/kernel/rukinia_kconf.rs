@@ -2,36 +2,25 @@ use std::fs;
 
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
 
+#[derive(Clone)]
 pub struct RukiniaKernelConf {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
     result: RukiniaResultEntry,
 }
 
-impl RukiniaProcess for RukiniaKernelConf {
-    fn get_rukinia_command() -> &'static str {
-        "rukinia_kconf"
-    }
-
-    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
-    where
-        Self: Sized,
-    {
-        let mut rukiniakernelconfg = RukiniaKernelConf {
-            arguments,
-            syntax,
-            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
-
+impl RukiniaKernelConf {
+    /// Compares the kernel configuration with the argument.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
         let path = "/proc/config.gz".to_string();
 
         match fs::read_to_string(&path) {
             Ok(content) => {
-                let test_succed = content.trim() == rukiniakernelconfg.arguments.first().unwrap();
+                let test_succed = content.trim() == self.arguments.first().unwrap();
                 if test_succed {
-                    rukiniakernelconfg.result.result_type = RukiniaResultType::TestSuccess;
+                    self.result.result_type = RukiniaResultType::TestSuccess;
                 }
             }
             Err(e) => {
@@ -39,15 +28,48 @@ impl RukiniaProcess for RukiniaKernelConf {
                     format!(
                         "{} {}",
                         RukiniaKernelConf::get_rukinia_command(),
-                        rukiniakernelconfg.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     e.to_string(),
                     "Failed to read /proc/config.gz".to_string(),
                 ));
             }
         }
-        rukiniakernelconfg.apply_syntax();
-        Ok(rukiniakernelconfg)
+        Ok(())
+    }
+}
+
+impl RukiniaProcess for RukiniaKernelConf {
+    fn get_rukinia_command() -> &'static str {
+        "rukinia_kconf"
+    }
+
+    fn get_description() -> &'static str {
+        "Checks the kernel configuration read from /proc/config.gz"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<config>"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 1, Some(1), Self::get_synopsis())
+    }
+
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    where
+        Self: Sized,
+    {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaKernelConf {
+            arguments,
+            syntax,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
     }
 
     fn get_result(&self) -> RukiniaResultEntry {
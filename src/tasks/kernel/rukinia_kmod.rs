@@ -2,43 +2,19 @@ use std::fs;
 
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
+#[derive(Clone)]
 pub struct RukiniaKernelMod {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
     result: RukiniaResultEntry,
 }
 
-impl RukiniaProcess for RukiniaKernelMod {
-    fn get_rukinia_command() -> &'static str {
-        "rukinia_kmod"
-    }
-
-    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
-    where
-        Self: Sized,
-    {
-        let mut rukinia_kernel_mod: RukiniaKernelMod = RukiniaKernelMod {
-            arguments,
-            syntax,
-            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
-
+impl RukiniaKernelMod {
+    /// Looks for the module in `/proc/modules`.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
         let path = "/proc/modules".to_string();
-        let module_name = match rukinia_kernel_mod.arguments.first() {
-            Some(name) => name.trim_matches('"'),
-            None => {
-                return Err(RukiniaError::new(
-                    format!(
-                        "{} {}",
-                        RukiniaKernelMod::get_rukinia_command(),
-                        rukinia_kernel_mod.arguments.join(" ")
-                    ),
-                    "Missing module name argument".to_string(),
-                    "No module name provided".to_string(),
-                ));
-            }
-        };
+        let module_name = self.arguments[0].as_str();
 
         match fs::read_to_string(&path) {
             Ok(content) => {
@@ -46,7 +22,7 @@ impl RukiniaProcess for RukiniaKernelMod {
                     .lines()
                     .any(|line| line.split_whitespace().next() == Some(module_name));
                 if result {
-                    rukinia_kernel_mod.result.result_type = RukiniaResultType::TestSuccess;
+                    self.result.result_type = RukiniaResultType::TestSuccess;
                 }
             }
             Err(e) => {
@@ -54,7 +30,7 @@ impl RukiniaProcess for RukiniaKernelMod {
                     format!(
                         "{} {}",
                         RukiniaKernelMod::get_rukinia_command(),
-                        rukinia_kernel_mod.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     e.to_string(),
                     "Failed to read /proc/modules".to_string(),
@@ -62,8 +38,41 @@ impl RukiniaProcess for RukiniaKernelMod {
             }
         }
 
-        rukinia_kernel_mod.apply_syntax();
-        Ok(rukinia_kernel_mod)
+        Ok(())
+    }
+}
+
+impl RukiniaProcess for RukiniaKernelMod {
+    fn get_rukinia_command() -> &'static str {
+        "rukinia_kmod"
+    }
+
+    fn get_description() -> &'static str {
+        "Checks a kernel module is loaded"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<kernel module>"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 1, Some(1), Self::get_synopsis())
+    }
+
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    where
+        Self: Sized,
+    {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaKernelMod {
+            arguments,
+            syntax,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
     }
 
     fn get_result(&self) -> RukiniaResultEntry {
@@ -2,38 +2,18 @@ use std::fs;
 
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
+#[derive(Clone)]
 pub struct RukiniaKernelThread {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
     result: RukiniaResultEntry,
 }
 
-impl RukiniaProcess for RukiniaKernelThread {
-    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
-    where
-        Self: Sized,
-    {
-        let mut rukinia_kernel_thread = RukiniaKernelThread {
-            arguments,
-            syntax,
-            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
-
-        let process_name = match rukinia_kernel_thread.arguments.first() {
-            Some(name) => name.trim_matches('"'),
-            None => {
-                return Err(RukiniaError::new(
-                    format!(
-                        "{} {}",
-                        RukiniaKernelThread::get_rukinia_command(),
-                        rukinia_kernel_thread.arguments.join(" ")
-                    ),
-                    "Missing process name argument".to_string(),
-                    "No process name provided".to_string(),
-                ));
-            }
-        };
+impl RukiniaKernelThread {
+    /// Looks for a kernel thread with the name in `/proc/*/status`.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
+        let process_name = self.arguments[0].as_str();
 
         match fs::read_dir("/proc") {
             Ok(entries) => {
@@ -51,18 +31,18 @@ impl RukiniaProcess for RukiniaKernelThread {
 
                     if let Ok(status) = fs::read_to_string(&status_file) {
                         for line in status.lines() {
-                            if line.starts_with("Name:\t") && line.split_whitespace().nth(1) == Some(process_name) {
-                                rukinia_kernel_thread.result.result_type =
-                                        RukiniaResultType::TestSuccess;
+                            if line.starts_with("Name:\t")
+                                && line.split_whitespace().nth(1) == Some(process_name)
+                            {
+                                self.result.result_type = RukiniaResultType::TestSuccess;
                             }
                             if line.starts_with("VmSize:") {
-                                rukinia_kernel_thread.result.result_type =
-                                    RukiniaResultType::TestFail;
+                                self.result.result_type = RukiniaResultType::TestFail;
                                 break;
                             }
                         }
                     }
-                    if rukinia_kernel_thread.result.result_type == RukiniaResultType::TestSuccess {
+                    if self.result.result_type == RukiniaResultType::TestSuccess {
                         break;
                     }
                 }
@@ -72,21 +52,50 @@ impl RukiniaProcess for RukiniaKernelThread {
                     format!(
                         "{} {}",
                         RukiniaKernelThread::get_rukinia_command(),
-                        rukinia_kernel_thread.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     e.to_string(),
                     "Failed to read /proc/modules".to_string(),
                 ));
             }
         }
-        rukinia_kernel_thread.apply_syntax();
-        Ok(rukinia_kernel_thread)
+        Ok(())
+    }
+}
+
+impl RukiniaProcess for RukiniaKernelThread {
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    where
+        Self: Sized,
+    {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaKernelThread {
+            arguments,
+            syntax,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
     }
 
     fn get_rukinia_command() -> &'static str {
         "rukinia_kthread"
     }
 
+    fn get_description() -> &'static str {
+        "Checks a kernel thread is running"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<pname>"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 1, Some(1), Self::get_synopsis())
+    }
+
     fn get_result(&self) -> RukiniaResultEntry {
         self.result.clone()
     }
@@ -1,3 +1,4 @@
+pub mod registry;
 pub mod task;
 
 pub mod bool;
@@ -11,5 +12,8 @@ pub mod kernel;
 #[cfg(feature = "network")]
 pub mod network;  
 
+#[cfg(feature = "plugin")]
+pub mod plugin;
+
 #[cfg(feature = "user")]
 pub mod user;
\ No newline at end of file
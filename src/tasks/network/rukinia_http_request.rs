@@ -1,6 +1,7 @@
+use crate::core::configuration::rukinia_tasks_settings;
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcessAsync;
+use crate::tasks::task::{check_argument_count, RukiniaProcess};
 
 pub struct RukiniaHttpReq {
     pub arguments: Vec<String>,
@@ -8,40 +9,65 @@ pub struct RukiniaHttpReq {
     result: RukiniaResultEntry,
 }
 
-impl RukiniaProcessAsync for RukiniaHttpReq {
+impl RukiniaProcess for RukiniaHttpReq {
     fn get_rukinia_command() -> &'static str {
         "rukinia_http_request"
     }
 
-    async fn async_new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    fn get_description() -> &'static str {
+        "Checks an http url returns 200"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<url>"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 1, Some(1), Self::get_synopsis())?;
+        let url = arguments[0].as_str();
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!(
+                "Expected an http:// or https:// url, got '{}'",
+                url
+            ));
+        }
+        Ok(())
+    }
+
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
     where
         Self: Sized,
     {
-        let mut runikia_http_req = RukiniaHttpReq {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaHttpReq {
             arguments,
             syntax,
             result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
+        })
+    }
 
-        let url = match runikia_http_req.arguments.first() {
-            Some(name) => name.trim_matches('"'),
-            None => {
-                return Err(RukiniaError::new(
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        let url = self.arguments[0].as_str();
+
+        let client = reqwest::Client::builder()
+            .timeout(rukinia_tasks_settings().http_request.get_timeout())
+            .build()
+            .map_err(|err| {
+                RukiniaError::new(
                     format!(
                         "{} {}",
                         RukiniaHttpReq::get_rukinia_command(),
-                        runikia_http_req.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
-                    "Missing url name argument".to_string(),
-                    "No url name provided".to_string(),
-                ));
-            }
-        };
+                    "HTTP client creation failed".to_string(),
+                    err.to_string(),
+                )
+            })?;
 
-        match reqwest::get(url).await {
+        match client.get(url).send().await {
             Ok(response) => {
                 if response.status().is_success() {
-                    runikia_http_req.result.result_type = RukiniaResultType::TestSuccess;
+                    self.result.result_type = RukiniaResultType::TestSuccess;
                 }
             }
             Err(err) => {
@@ -49,15 +75,15 @@ impl RukiniaProcessAsync for RukiniaHttpReq {
                     format!(
                         "{} {}",
                         RukiniaHttpReq::get_rukinia_command(),
-                        runikia_http_req.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "HTTP REQWEST failed".to_string(),
                     err.to_string(),
                 ));
             }
         }
-        runikia_http_req.apply_syntax();
-        Ok(runikia_http_req)
+        self.apply_syntax();
+        Ok(())
     }
 
     fn get_result(&self) -> RukiniaResultEntry {
@@ -1,7 +1,8 @@
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
 use nix::ifaddrs::getifaddrs;
+#[derive(Clone)]
 pub struct RukiniaNetifHasIp {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
@@ -10,46 +11,10 @@ pub struct RukiniaNetifHasIp {
     extra_flags: String,
 }
 
-impl RukiniaProcess for RukiniaNetifHasIp {
-    fn get_rukinia_command() -> &'static str {
-        "rukinia_netif_has_ip"
-    }
-
-    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
-    where
-        Self: Sized,
-    {
-        let mut rukinia_net_if_has_ip = RukiniaNetifHasIp {
-            arguments,
-            syntax,
-            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-            ip_version: String::from("-4"),
-            extra_flags: String::new(),
-        };
-
-        for arg in rukinia_net_if_has_ip.arguments.iter().skip(1) {
-            if arg == "-6" {
-                rukinia_net_if_has_ip.ip_version = String::from("-6");
-            } else {
-                rukinia_net_if_has_ip.extra_flags.push_str(arg);
-                rukinia_net_if_has_ip.extra_flags.push(' ');
-            }
-        }
-
-        let iface_name = match rukinia_net_if_has_ip.arguments.first() {
-            Some(name) => name.trim_matches('"'),
-            None => {
-                return Err(RukiniaError::new(
-                    format!(
-                        "{} {}",
-                        RukiniaNetifHasIp::get_rukinia_command(),
-                        rukinia_net_if_has_ip.arguments.join(" ")
-                    ),
-                    "Missing interface name argument".to_string(),
-                    "No interface name provided".to_string(),
-                ));
-            }
-        };
+impl RukiniaNetifHasIp {
+    /// Looks for an address of the IP version and flags on the interface.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
+        let iface_name = self.arguments[0].as_str();
 
         let ifaddrs = match getifaddrs() {
             Ok(addrs) => addrs,
@@ -58,7 +23,7 @@ impl RukiniaProcess for RukiniaNetifHasIp {
                     format!(
                         "{} {}",
                         RukiniaNetifHasIp::get_rukinia_command(),
-                        rukinia_net_if_has_ip.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "Failed to retrieve network interfaces".to_string(),
                     err.to_string(),
@@ -78,19 +43,18 @@ impl RukiniaProcess for RukiniaNetifHasIp {
             })
             .any(|(flags, address)| {
                 let flags_str = flags.to_string().to_lowercase();
-                let extra_flags_lower = rukinia_net_if_has_ip.extra_flags.trim().to_lowercase();
+                let extra_flags_lower = self.extra_flags.trim().to_lowercase();
                 let flags_contains_extra = flags_str.contains(&extra_flags_lower);
 
-                if !rukinia_net_if_has_ip.extra_flags.trim().is_empty() && !flags_contains_extra {
+                if !self.extra_flags.trim().is_empty() && !flags_contains_extra {
                     return false;
                 }
 
                 if let Some(addr) = address {
-                    if addr.as_sockaddr_in().is_some() && rukinia_net_if_has_ip.ip_version == "-4" {
+                    if addr.as_sockaddr_in().is_some() && self.ip_version == "-4" {
                         return true;
                     }
-                    if addr.as_sockaddr_in6().is_some() && rukinia_net_if_has_ip.ip_version == "-6"
-                    {
+                    if addr.as_sockaddr_in6().is_some() && self.ip_version == "-6" {
                         return true;
                     }
                 }
@@ -98,12 +62,58 @@ impl RukiniaProcess for RukiniaNetifHasIp {
             });
 
         if result {
-            rukinia_net_if_has_ip.result.result_type = RukiniaResultType::TestSuccess;
+            self.result.result_type = RukiniaResultType::TestSuccess;
         }
-        rukinia_net_if_has_ip.apply_syntax();
+        Ok(())
+    }
+}
+
+impl RukiniaProcess for RukiniaNetifHasIp {
+    fn get_rukinia_command() -> &'static str {
+        "rukinia_netif_has_ip"
+    }
+
+    fn get_description() -> &'static str {
+        "Checks a network interface has an ip address assigned"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<interface> [-4|-6] [flags]"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 1, None, Self::get_synopsis())
+    }
+
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    where
+        Self: Sized,
+    {
+        Self::validate_arguments(&arguments)?;
+        let mut rukinia_net_if_has_ip = RukiniaNetifHasIp {
+            arguments,
+            syntax,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+            ip_version: String::from("-4"),
+            extra_flags: String::new(),
+        };
+
+        for arg in rukinia_net_if_has_ip.arguments.iter().skip(1) {
+            if arg == "-6" {
+                rukinia_net_if_has_ip.ip_version = String::from("-6");
+            } else {
+                rukinia_net_if_has_ip.extra_flags.push_str(arg);
+                rukinia_net_if_has_ip.extra_flags.push(' ');
+            }
+        }
+
         Ok(rukinia_net_if_has_ip)
     }
 
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
+    }
+
     fn get_result(&self) -> RukiniaResultEntry {
         self.result.clone()
     }
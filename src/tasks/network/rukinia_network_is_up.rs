@@ -2,49 +2,25 @@ use std::fs;
 
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
+#[derive(Clone)]
 pub struct RukiniaNetworkIsUp {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
     result: RukiniaResultEntry,
 }
 
-impl RukiniaProcess for RukiniaNetworkIsUp {
-    fn get_rukinia_command() -> &'static str {
-        "rukinia_netif_is_up"
-    }
-
-    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
-    where
-        Self: Sized,
-    {
-        let mut rukinia_network_is_up = RukiniaNetworkIsUp {
-            arguments,
-            syntax,
-            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
-
-        let pid = match rukinia_network_is_up.arguments.first() {
-            Some(name) => name.trim_matches('"'),
-            None => {
-                return Err(RukiniaError::new(
-                    format!(
-                        "{} {}",
-                        RukiniaNetworkIsUp::get_rukinia_command(),
-                        rukinia_network_is_up.arguments.join(" ")
-                    ),
-                    "Missing pid argument".to_string(),
-                    "No pid provided".to_string(),
-                ));
-            }
-        };
+impl RukiniaNetworkIsUp {
+    /// Reads the operational state of the interface in `/sys/class/net`.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
+        let pid = self.arguments[0].as_str();
 
         let path = format!("/sys/class/net/{}/operstate", pid);
 
         match fs::read_to_string(&path) {
             Ok(content) => {
                 if content.trim() == "up" {
-                    rukinia_network_is_up.result.result_type = RukiniaResultType::TestSuccess;
+                    self.result.result_type = RukiniaResultType::TestSuccess;
                 }
             }
             Err(err) => {
@@ -52,7 +28,7 @@ impl RukiniaProcess for RukiniaNetworkIsUp {
                     format!(
                         "{} {}",
                         RukiniaNetworkIsUp::get_rukinia_command(),
-                        rukinia_network_is_up.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "Failed to read network interface operstate".to_string(),
                     err.to_string(),
@@ -60,8 +36,41 @@ impl RukiniaProcess for RukiniaNetworkIsUp {
             }
         }
 
-        rukinia_network_is_up.apply_syntax();
-        Ok(rukinia_network_is_up)
+        Ok(())
+    }
+}
+
+impl RukiniaProcess for RukiniaNetworkIsUp {
+    fn get_rukinia_command() -> &'static str {
+        "rukinia_netif_is_up"
+    }
+
+    fn get_description() -> &'static str {
+        "Checks a network interface is up"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<interface>"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 1, Some(1), Self::get_synopsis())
+    }
+
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    where
+        Self: Sized,
+    {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaNetworkIsUp {
+            arguments,
+            syntax,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
     }
 
     fn get_result(&self) -> RukiniaResultEntry {
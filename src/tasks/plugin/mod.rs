@@ -0,0 +1 @@
+pub mod rukinia_plugin;
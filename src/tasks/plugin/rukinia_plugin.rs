@@ -0,0 +1,336 @@
+//! Tasks provided by external executables.
+//!
+//! Any executable named `rukinia-task-<name>` found in the plugin paths of the settings is
+//! available in test plans as `rukinia_<name>`. The plugin receives the arguments of the
+//! task on its command line and prints a JSON document on its standard output:
+//!
+//! ```json
+//! {"result": "pass", "label": "Modem answers", "details": "AT OK", "metrics": {"latency_ms": 12}}
+//! ```
+//!
+//! * `result` - `pass`, `fail`, or `error` if the check could not be done
+//! * `label` - Label of the result, `rukinia_<name>` followed by the arguments when omitted
+//! * `details` - Text written in the reports, the error message when `result` is `error`
+//! * `metrics` - Numbers written in the reports, by name
+//!
+//! A plugin still running after the `timeout` of the `[tasks.plugins]` settings is killed,
+//! and reported as an error.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::future::Future;
+use std::io::Read;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::{Command, Output, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::core::configuration::rukinia_tasks_settings;
+use crate::core::rukinia_result::*;
+use crate::core::syntax::SyntaxForTrait;
+use crate::tasks::registry::{rukinia_find_task, rukinia_register_task, RukiniaTaskEntry};
+use crate::tasks::task::{apply_syntax_to_result, run_probe, BuiltTask, RukiniaTask};
+
+/// Prefix of the executables providing a task.
+pub const PLUGIN_PREFIX: &str = "rukinia-task-";
+
+/// Interval at which a running plugin is checked for its exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Outcome of a plugin, the `result` key of its output.
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginResult {
+    Pass,
+    Fail,
+    Skip,
+    Error,
+}
+
+/// JSON document printed by a plugin on its standard output.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct PluginOutput {
+    pub result: PluginResult,
+    pub label: Option<String>,
+    pub details: Option<String>,
+    #[serde(default)]
+    pub metrics: BTreeMap<String, f64>,
+}
+
+/// A task run by a plugin executable.
+#[derive(Clone)]
+pub struct RukiniaPlugin {
+    pub name: String,
+    pub executable: PathBuf,
+    pub arguments: Vec<String>,
+    pub syntax: SyntaxForTrait,
+    /// Time the executable may run, see `PluginsConfig::timeout`.
+    pub timeout: Duration,
+    label: Option<String>,
+    result: RukiniaResultEntry,
+}
+
+impl RukiniaPlugin {
+    /// Creates the task run by `executable` as `name`, without running it.
+    ///
+    /// Its timeout is the one of the `[tasks.plugins]` settings.
+    pub fn new(
+        name: &str,
+        executable: &Path,
+        arguments: Vec<String>,
+        syntax: SyntaxForTrait,
+    ) -> Self {
+        RukiniaPlugin {
+            name: name.to_string(),
+            executable: executable.to_path_buf(),
+            arguments,
+            syntax,
+            timeout: rukinia_tasks_settings().plugins.get_timeout(),
+            label: None,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+        }
+    }
+
+    fn error(&self, system_error: String, message: String) -> RukiniaError {
+        RukiniaError::new(
+            format!("{} {}", self.name, self.arguments.join(" "))
+                .trim_end()
+                .to_string(),
+            system_error,
+            message,
+        )
+    }
+
+    /// Sets the time the executable may run before being killed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Runs the executable, killing it if it does not exit within the timeout.
+    fn execute(&self) -> Result<Output, RukiniaError> {
+        let mut child = Command::new(&self.executable)
+            .args(&self.arguments)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                self.error(
+                    e.to_string(),
+                    format!("Failed to run {}", self.executable.display()),
+                )
+            })?;
+        // Read while the plugin runs, so that it never blocks on a full pipe
+        let stdout = read_pipe(child.stdout.take());
+        let stderr = read_pipe(child.stderr.take());
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() < deadline => thread::sleep(POLL_INTERVAL),
+                Ok(None) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(self.error(
+                        "Plugin timed out".to_string(),
+                        format!(
+                            "{} did not exit within {} seconds",
+                            self.executable.display(),
+                            self.timeout.as_secs_f64()
+                        ),
+                    ));
+                }
+                Err(e) => {
+                    return Err(self.error(
+                        e.to_string(),
+                        format!("Failed to wait for {}", self.executable.display()),
+                    ))
+                }
+            }
+        };
+
+        Ok(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        })
+    }
+
+    /// Runs the executable and reads the JSON document it prints.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
+        let output = self.execute()?;
+
+        let plugin_output: PluginOutput = serde_json::from_slice(&output.stdout).map_err(|e| {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            self.error(
+                "Invalid plugin output".to_string(),
+                format!(
+                    "{} ({}){}",
+                    e,
+                    output.status,
+                    if stderr.trim().is_empty() {
+                        String::new()
+                    } else {
+                        format!(": {}", stderr.trim())
+                    }
+                ),
+            )
+        })?;
+
+        if plugin_output.result == PluginResult::Error {
+            return Err(self.error(
+                "Plugin error".to_string(),
+                plugin_output
+                    .details
+                    .unwrap_or_else(|| "The plugin did not give any details".to_string()),
+            ));
+        }
+
+        self.result.result_type = match plugin_output.result {
+            PluginResult::Pass => RukiniaResultType::TestSuccess,
+            PluginResult::Skip => RukiniaResultType::TestSkip,
+            PluginResult::Fail | PluginResult::Error => RukiniaResultType::TestFail,
+        };
+        self.label = plugin_output.label;
+        self.result.details = plugin_output.details;
+        self.result.metrics = plugin_output.metrics;
+        Ok(())
+    }
+
+    async fn run_plugin(&mut self) -> Result<(), RukiniaError> {
+        let name = self.name.clone();
+        run_probe(self, &name, RukiniaPlugin::probe).await?;
+        self.result =
+            apply_syntax_to_result(self.result.clone(), &self.syntax, || self.display_format());
+        Ok(())
+    }
+}
+
+impl From<RukiniaPlugin> for RukiniaTask {
+    fn from(plugin: RukiniaPlugin) -> Self {
+        RukiniaTask::from_built(plugin)
+    }
+}
+
+/// Reads a pipe of a plugin to its end on another thread.
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+impl BuiltTask for RukiniaPlugin {
+    fn run(&mut self) -> Pin<Box<dyn Future<Output = Result<(), RukiniaError>> + Send + '_>> {
+        Box::pin(self.run_plugin())
+    }
+
+    fn get_result(&self) -> RukiniaResultEntry {
+        self.result.clone()
+    }
+
+    /// The label given by the plugin, or the task and its arguments before it is run.
+    fn display_format(&self) -> String {
+        format!(
+            "{}{}",
+            if self.syntax.contains_not() {
+                "not "
+            } else {
+                ""
+            },
+            self.label.clone().unwrap_or_else(|| {
+                format!("{} {}", self.name, self.arguments.join(" "))
+                    .trim_end()
+                    .to_string()
+            })
+        )
+    }
+}
+
+/// Returns the name of the task provided by a plugin executable, if it is one.
+fn plugin_task_name(path: &Path) -> Option<String> {
+    let suffix = path.file_name()?.to_str()?.strip_prefix(PLUGIN_PREFIX)?;
+    let is_executable = fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false);
+    if suffix.is_empty() || !is_executable {
+        return None;
+    }
+    Some(format!("rukinia_{}", suffix))
+}
+
+/// Returns the registry entries of the plugins found in `paths`, in the order of the
+/// paths then of the file names.
+///
+/// Missing directories are ignored, as are plugins whose task is provided by an earlier
+/// path.
+///
+/// # Arguments
+/// * `paths` - Directories holding `rukinia-task-<name>` executables
+pub fn rukinia_find_plugins(paths: &[String]) -> Vec<RukiniaTaskEntry> {
+    let mut entries: Vec<RukiniaTaskEntry> = Vec::new();
+    for path in paths {
+        let Ok(directory) = fs::read_dir(path) else {
+            continue;
+        };
+        let mut executables: Vec<PathBuf> = directory
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        executables.sort();
+
+        for executable in executables {
+            let Some(name) = plugin_task_name(&executable) else {
+                continue;
+            };
+            if entries.iter().any(|entry| entry.name == name) {
+                continue;
+            }
+
+            let task_name = name.clone();
+            let task_executable = executable.clone();
+            entries.push(
+                RukiniaTaskEntry::new(
+                    &name,
+                    &format!("Plugin {}", executable.display()),
+                    "[argument...]",
+                    Arc::new(|_| Ok(())),
+                    Arc::new(move |arguments, syntax| {
+                        Ok(
+                            RukiniaPlugin::new(&task_name, &task_executable, arguments, syntax)
+                                .into(),
+                        )
+                    }),
+                )
+                .with_feature("plugin"),
+            );
+        }
+    }
+    entries
+}
+
+/// Registers the plugins found in `paths`, see `rukinia_find_plugins`.
+///
+/// Plugins named like a task already registered, such as a built-in task, are ignored.
+///
+/// # Returns
+/// The names of the tasks registered.
+pub fn rukinia_register_plugins(paths: &[String]) -> Vec<String> {
+    rukinia_find_plugins(paths)
+        .into_iter()
+        .filter(|entry| rukinia_find_task(&entry.name).is_none())
+        .filter_map(|entry| {
+            let name = entry.name.clone();
+            rukinia_register_task(entry).ok().map(|_| name)
+        })
+        .collect()
+}
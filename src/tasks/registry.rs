@@ -0,0 +1,268 @@
+//! Registry of the tasks available in test plans.
+//!
+//! The registry maps the name of a task in a test plan to the factory building it from its
+//! arguments. The built-in tasks of the enabled features are registered when the registry is
+//! first used; programs using rukinia as a library add their own tasks at startup with
+//! `rukinia_register_task`, before the test plans are parsed.
+//!
+//! # Example
+//! ```rust,ignore
+//! rukinia_register_task(RukiniaTaskEntry::of::<BoardHasModem>())?;
+//! ```
+
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::core::rukinia_result::RukiniaError;
+use crate::core::syntax::SyntaxForTrait;
+use crate::tasks::task::{RukiniaProcess, RukiniaTask};
+
+#[cfg(feature = "kernel")]
+use crate::tasks::kernel::{
+    rukinia_kconf::RukiniaKernelConf, rukinia_kmod::RukiniaKernelMod,
+    rukinia_kthread::RukiniaKernelThread,
+};
+
+#[cfg(feature = "network")]
+use crate::tasks::network::{
+    rukinia_http_request::RukiniaHttpReq, rukinia_netif_has_ip::RukiniaNetifHasIp,
+    rukinia_network_is_up::RukiniaNetworkIsUp,
+};
+
+#[cfg(feature = "user")]
+use crate::tasks::user::{
+    rukinia_group::RukiniaGroup, rukinia_user::RukiniaUser,
+    rukinia_user_memberof::RukiniaUserMemberOf,
+};
+
+#[cfg(feature = "filesystem")]
+use crate::tasks::filesystem::{rukinia_cmd::RukiniaCmd, rukinia_symlink::RukiniaSymlink};
+
+use crate::tasks::bool::{rukinia_false::RukiniaFalse, rukinia_true::RukiniaTrue};
+
+/// Checks the arguments of a task without building it, see `RukiniaProcess::check_arguments`.
+pub type RukiniaArgumentsCheck = Arc<dyn Fn(&[String]) -> Result<(), String> + Send + Sync>;
+
+/// Builds a task from its arguments and syntax rules, see `RukiniaProcess::new`.
+pub type RukiniaTaskFactory =
+    Arc<dyn Fn(Vec<String>, SyntaxForTrait) -> Result<RukiniaTask, RukiniaError> + Send + Sync>;
+
+/// A task of the registry: its name in test plans, its metadata and its factory.
+#[derive(Clone)]
+pub struct RukiniaTaskEntry {
+    /// Name of the task in test plans.
+    pub name: String,
+    /// One line description of what the task checks.
+    pub description: String,
+    /// Arguments expected by the task, e.g. `<link> <target>`, empty if it takes none.
+    pub synopsis: String,
+    /// Cargo feature providing the task, `None` for the tasks always available.
+    pub feature: Option<String>,
+    check_arguments: RukiniaArgumentsCheck,
+    factory: RukiniaTaskFactory,
+}
+
+impl RukiniaTaskEntry {
+    /// Creates an entry from its metadata, its arguments check and its factory.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the task in test plans
+    /// * `description` - One line description of what the task checks
+    /// * `synopsis` - Arguments expected by the task
+    /// * `check_arguments` - Checks the arguments without building the task
+    /// * `factory` - Builds the task from its arguments and syntax rules
+    pub fn new(
+        name: &str,
+        description: &str,
+        synopsis: &str,
+        check_arguments: RukiniaArgumentsCheck,
+        factory: RukiniaTaskFactory,
+    ) -> Self {
+        RukiniaTaskEntry {
+            name: name.to_string(),
+            description: description.to_string(),
+            synopsis: synopsis.to_string(),
+            feature: None,
+            check_arguments,
+            factory,
+        }
+    }
+
+    /// Creates the entry of a task implementing `RukiniaProcess`, its metadata being
+    /// those returned by the trait.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let entry = RukiniaTaskEntry::of::<RukiniaUser>();
+    /// assert_eq!(entry.name, "rukinia_user");
+    /// ```
+    pub fn of<T: RukiniaProcess + 'static>() -> Self {
+        RukiniaTaskEntry::new(
+            T::get_rukinia_command(),
+            T::get_description(),
+            T::get_synopsis(),
+            Arc::new(|arguments| T::check_arguments(arguments)),
+            Arc::new(|arguments, syntax| T::new(arguments, syntax).map(RukiniaTask::new)),
+        )
+    }
+
+    /// Records the Cargo feature providing the task.
+    pub fn with_feature(mut self, feature: &str) -> Self {
+        self.feature = Some(feature.to_string());
+        self
+    }
+
+    /// Checks the arguments of the task without building it.
+    ///
+    /// # Returns
+    /// `Err` with a description of the problem if the arguments are invalid
+    pub fn check_arguments(&self, arguments: &[String]) -> Result<(), String> {
+        (self.check_arguments)(arguments)
+    }
+
+    /// Builds the task from its arguments and syntax rules, without running it.
+    ///
+    /// # Errors
+    /// Returns a `RukiniaError` if the arguments are invalid.
+    pub fn build(
+        &self,
+        arguments: Vec<String>,
+        syntax: SyntaxForTrait,
+    ) -> Result<RukiniaTask, RukiniaError> {
+        (self.factory)(arguments, syntax)
+    }
+}
+
+/// Tasks available in test plans, in the order they were registered.
+#[derive(Clone, Default)]
+pub struct RukiniaTaskRegistry {
+    tasks: Vec<Arc<RukiniaTaskEntry>>,
+}
+
+impl RukiniaTaskRegistry {
+    /// Creates a registry holding the built-in tasks of the enabled features.
+    pub fn with_builtin_tasks() -> Self {
+        let mut tasks: Vec<RukiniaTaskEntry> = Vec::new();
+        #[cfg(feature = "network")]
+        tasks.extend(
+            [
+                RukiniaTaskEntry::of::<RukiniaNetworkIsUp>(),
+                RukiniaTaskEntry::of::<RukiniaNetifHasIp>(),
+                RukiniaTaskEntry::of::<RukiniaHttpReq>(),
+            ]
+            .map(|entry| entry.with_feature("network")),
+        );
+        #[cfg(feature = "user")]
+        tasks.extend(
+            [
+                RukiniaTaskEntry::of::<RukiniaUser>(),
+                RukiniaTaskEntry::of::<RukiniaGroup>(),
+                RukiniaTaskEntry::of::<RukiniaUserMemberOf>(),
+            ]
+            .map(|entry| entry.with_feature("user")),
+        );
+        #[cfg(feature = "kernel")]
+        tasks.extend(
+            [
+                RukiniaTaskEntry::of::<RukiniaKernelMod>(),
+                RukiniaTaskEntry::of::<RukiniaKernelThread>(),
+                RukiniaTaskEntry::of::<RukiniaKernelConf>(),
+            ]
+            .map(|entry| entry.with_feature("kernel")),
+        );
+        #[cfg(feature = "filesystem")]
+        tasks.extend(
+            [
+                RukiniaTaskEntry::of::<RukiniaSymlink>(),
+                RukiniaTaskEntry::of::<RukiniaCmd>(),
+            ]
+            .map(|entry| entry.with_feature("filesystem")),
+        );
+        tasks.extend([
+            RukiniaTaskEntry::of::<RukiniaTrue>(),
+            RukiniaTaskEntry::of::<RukiniaFalse>(),
+        ]);
+
+        RukiniaTaskRegistry {
+            tasks: tasks.into_iter().map(Arc::new).collect(),
+        }
+    }
+
+    /// Adds a task to the registry.
+    ///
+    /// # Errors
+    /// Returns a `RukiniaError` if the name of the task is not a single word or is already
+    /// registered.
+    pub fn register(&mut self, entry: RukiniaTaskEntry) -> Result<(), RukiniaError> {
+        let problem = if entry.name.is_empty()
+            || entry
+                .name
+                .chars()
+                .any(|c| c.is_whitespace() || "()\"'".contains(c))
+        {
+            Some("The task name must be a single word".to_string())
+        } else if self.get(&entry.name).is_some() {
+            Some(format!("The task '{}' is already registered", entry.name))
+        } else {
+            None
+        };
+        if let Some(message) = problem {
+            return Err(RukiniaError::new(
+                entry.name,
+                "Failed to register task".to_string(),
+                message,
+            ));
+        }
+
+        self.tasks.push(Arc::new(entry));
+        Ok(())
+    }
+
+    /// Returns the task registered with this name.
+    pub fn get(&self, name: &str) -> Option<Arc<RukiniaTaskEntry>> {
+        self.tasks.iter().find(|task| task.name == name).cloned()
+    }
+
+    /// Returns every task, in the order they were registered.
+    pub fn tasks(&self) -> &[Arc<RukiniaTaskEntry>] {
+        &self.tasks
+    }
+}
+
+static TASK_REGISTRY: OnceLock<RwLock<RukiniaTaskRegistry>> = OnceLock::new();
+
+fn task_registry() -> &'static RwLock<RukiniaTaskRegistry> {
+    TASK_REGISTRY.get_or_init(|| RwLock::new(RukiniaTaskRegistry::with_builtin_tasks()))
+}
+
+/// Adds a task to the registry used by test plans, see `RukiniaTaskRegistry::register`.
+///
+/// # Errors
+/// Returns a `RukiniaError` if the name of the task is invalid or already registered.
+///
+/// # Examples
+/// ```rust,ignore
+/// rukinia_register_task(RukiniaTaskEntry::of::<BoardHasModem>().with_feature("board"))?;
+/// ```
+pub fn rukinia_register_task(entry: RukiniaTaskEntry) -> Result<(), RukiniaError> {
+    task_registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .register(entry)
+}
+
+/// Returns the task registered with this name in the registry used by test plans.
+pub fn rukinia_find_task(name: &str) -> Option<Arc<RukiniaTaskEntry>> {
+    task_registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+}
+
+/// Returns every task of the registry used by test plans, in the order they were registered.
+pub fn rukinia_registered_tasks() -> Vec<Arc<RukiniaTaskEntry>> {
+    task_registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .tasks()
+        .to_vec()
+}
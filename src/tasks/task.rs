@@ -1,27 +1,18 @@
-//! Module containing all Rukinia tasks and their core traits.
+//! Module containing the core traits of Rukinia tasks.
 //!
 //! This module defines:
-//! - The enum `RukiniaAllTasks` that represents all available tasks
-//! - The traits `RukiniaProcess` and `RukiniaProcessAsync` for synchronous and asynchronous task processing
+//! - The struct `RukiniaAllTasks` that represents a task of the registry, found by its name in a test plan
+//! - The struct `RukiniaTask` holding a task built from its arguments, ready to be run
+//! - The trait `RukiniaProcess` implemented by every task, and `run_blocking` for tasks probing the system with blocking calls
 //! - Implementations for executing tasks and converting from string representations
 
+use std::fmt;
 use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 
 use crate::core::syntax::SyntaxForTrait;
-
-#[cfg(feature = "kernel")]
-use crate::tasks::kernel::{rukinia_kconf::RukiniaKernelConf,rukinia_kmod::RukiniaKernelMod,rukinia_kthread::RukiniaKernelThread};
-
-#[cfg(feature = "network")]
-use crate::tasks::network::{rukinia_http_request::RukiniaHttpReq, rukinia_netif_has_ip::RukiniaNetifHasIp, rukinia_network_is_up::RukiniaNetworkIsUp};
-
-#[cfg(feature = "user")]
-use crate::tasks::user::{rukinia_user::RukiniaUser, rukinia_user_memberof::RukiniaUserMemberOf, rukinia_group::RukiniaGroup};
-
-#[cfg(feature = "filesystem")]
-use crate::tasks::filesystem::{rukinia_cmd::RukiniaCmd,rukinia_symlink::RukiniaSymlink};
-
-use crate::tasks::bool::{rukinia_false::RukiniaFalse,rukinia_true::RukiniaTrue};
+use crate::tasks::registry::{rukinia_find_task, rukinia_registered_tasks, RukiniaTaskEntry};
 
 use crate::core::rukinia_result::RukiniaError;
 use crate::core::rukinia_result::RukiniaResultEntry;
@@ -29,39 +20,75 @@ use crate::core::rukinia_result::RukiniaResultType;
 
 use std::str::FromStr;
 
-/// Enum representing all available Rukinia tasks.
+/// A task available in test plans, see `crate::tasks::registry`.
 ///
-/// Each variant corresponds to a specific test or check that Rukinia can perform.
+/// Two tasks are equal if they have the same name.
 #[derive(Clone)]
-pub enum RukiniaAllTasks {
-    #[cfg(feature = "network")]
-    NetworkIsUp,
-    #[cfg(feature = "network")]
-    NetifHasIp,
-    #[cfg(feature = "network")]
-    HttpReq,
-    #[cfg(feature = "user")]
-    User,
-    #[cfg(feature = "user")]
-    Group,
-    #[cfg(feature = "user")]
-    UserMemberOf,
-    #[cfg(feature = "kernel")]
-    KernelMod,
-    #[cfg(feature = "kernel")]
-    KernelThread,
-    #[cfg(feature = "kernel")]
-    KernelConf,
-    #[cfg(feature = "filesystem")]
-    Symlink,
-    #[cfg(feature = "filesystem")]
-    Cmd,
-    True,
-    False,
+pub struct RukiniaAllTasks {
+    entry: Arc<RukiniaTaskEntry>,
 }
 
 impl RukiniaAllTasks {
-    /// Execute the task with given arguments and syntax rules.
+    /// Every task of the registry, in the order they were registered.
+    pub fn all() -> Vec<RukiniaAllTasks> {
+        rukinia_registered_tasks()
+            .into_iter()
+            .map(|entry| RukiniaAllTasks { entry })
+            .collect()
+    }
+
+    /// Get the command string that identifies the task in a test plan.
+    pub fn get_rukinia_command(&self) -> &str {
+        &self.entry.name
+    }
+
+    /// Get the entry of the task in the registry, holding its metadata.
+    pub fn get_entry(&self) -> &RukiniaTaskEntry {
+        &self.entry
+    }
+
+    /// Check the arguments of the task without running it.
+    ///
+    /// # Arguments
+    /// * `arguments` - The arguments following the task name in the test plan
+    ///
+    /// # Returns
+    /// `Err` with a description of the problem if the arguments are invalid
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let symlink = RukiniaAllTasks::from_str("rukinia_symlink").unwrap();
+    /// assert!(symlink.check_arguments(&["/bin/sh".to_string()]).is_err());
+    /// ```
+    pub fn check_arguments(&self, arguments: &[String]) -> Result<(), String> {
+        self.entry.check_arguments(arguments)
+    }
+
+    /// Build the task from its arguments and syntax rules, without running it.
+    ///
+    /// # Arguments
+    /// * `arguments` - Vector of string arguments for the task
+    /// * `syntax` - Syntax rules to apply to the task
+    ///
+    /// # Returns
+    /// `Result<RukiniaTask, RukiniaError>` containing the task ready to be run, or an error
+    /// if its arguments are invalid
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// let network_is_up = RukiniaAllTasks::from_str("rukinia_netif_is_up").unwrap();
+    /// let task = network_is_up.build(vec!["eth0".to_string()], syntax)?;
+    /// let result = task.run().await;
+    /// ```
+    pub fn build(
+        &self,
+        arguments: Vec<String>,
+        syntax: SyntaxForTrait,
+    ) -> Result<RukiniaTask, RukiniaError> {
+        self.entry.build(arguments, syntax)
+    }
+
+    /// Build the task with given arguments and syntax rules, then run it.
     ///
     /// # Arguments
     /// * `arguments` - Vector of string arguments for the task
@@ -72,7 +99,7 @@ impl RukiniaAllTasks {
     ///
     /// # Examples
     /// ```rust,ignore
-    /// let task = RukiniaAllTasks::NetworkIsUp;
+    /// let task = RukiniaAllTasks::from_str("rukinia_netif_is_up").unwrap();
     /// let result = task.execute(vec!["eth0".to_string()], syntax).await;
     /// ```
     pub async fn execute(
@@ -80,136 +107,198 @@ impl RukiniaAllTasks {
         arguments: Vec<String>,
         syntax: SyntaxForTrait,
     ) -> Result<RukiniaResultEntry, RukiniaError> {
-        match self {
-            #[cfg(feature = "network")]
-            RukiniaAllTasks::NetworkIsUp => match RukiniaNetworkIsUp::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "network")]
-            RukiniaAllTasks::NetifHasIp => match RukiniaNetifHasIp::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "network")]
-            RukiniaAllTasks::HttpReq => match RukiniaHttpReq::async_new(arguments, syntax).await {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "user")]
-            RukiniaAllTasks::User => match RukiniaUser::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "user")]
-            RukiniaAllTasks::Group => match RukiniaGroup::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "user")]
-            RukiniaAllTasks::UserMemberOf => match RukiniaUserMemberOf::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "kernel")]
-            RukiniaAllTasks::KernelConf => match RukiniaKernelConf::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "kernel")]
-            RukiniaAllTasks::KernelMod => match RukiniaKernelMod::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "kernel")]
-            RukiniaAllTasks::KernelThread => match RukiniaKernelThread::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "filesystem")]
-            RukiniaAllTasks::Symlink => match RukiniaSymlink::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            #[cfg(feature = "filesystem")]
-            RukiniaAllTasks::Cmd => match RukiniaCmd::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            RukiniaAllTasks::True => match RukiniaTrue::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
-            RukiniaAllTasks::False => match RukiniaFalse::new(arguments, syntax) {
-                Ok(rukinia_valid) => Ok(rukinia_valid.get_result()),
-                Err(rukinia_error) => Err(rukinia_error),
-            },
+        self.build(arguments, syntax)?.run().await
+    }
+}
+
+impl PartialEq for RukiniaAllTasks {
+    fn eq(&self, other: &Self) -> bool {
+        self.entry.name == other.entry.name
+    }
+}
+
+impl fmt::Debug for RukiniaAllTasks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RukiniaAllTasks")
+            .field(&self.entry.name)
+            .finish()
+    }
+}
+
+/// A task built from its arguments by `RukiniaAllTasks::build`, ready to be run.
+pub struct RukiniaTask {
+    task: Box<dyn BuiltTask>,
+}
+
+impl RukiniaTask {
+    /// Wrap a task built with `RukiniaProcess::new`.
+    pub fn new<T: RukiniaProcess + 'static>(task: T) -> Self {
+        RukiniaTask {
+            task: Box::new(task),
         }
     }
+
+    /// Run the task, probing the system.
+    ///
+    /// # Returns
+    /// `Result<RukiniaResultEntry, RukiniaError>` containing the test result or error
+    pub async fn run(mut self) -> Result<RukiniaResultEntry, RukiniaError> {
+        self.task.run().await?;
+        Ok(self.task.get_result())
+    }
+
+    /// Get the label the result of the task will have, without running it.
+    pub fn display_format(&self) -> String {
+        self.task.display_format()
+    }
+
+    /// Wrap a task whose name is only known at runtime, such as a plugin.
+    #[cfg(feature = "plugin")]
+    pub(crate) fn from_built<T: BuiltTask + 'static>(task: T) -> Self {
+        RukiniaTask {
+            task: Box::new(task),
+        }
+    }
+}
+
+/// Object-safe part of `RukiniaProcess`, so that any task can be held by a `RukiniaTask`.
+///
+/// Implemented directly by the tasks that cannot implement `RukiniaProcess` because their
+/// name is only known at runtime.
+pub(crate) trait BuiltTask: Send {
+    fn run(&mut self) -> Pin<Box<dyn Future<Output = Result<(), RukiniaError>> + Send + '_>>;
+
+    fn get_result(&self) -> RukiniaResultEntry;
+
+    fn display_format(&self) -> String;
+}
+
+impl<T: RukiniaProcess> BuiltTask for T {
+    fn run(&mut self) -> Pin<Box<dyn Future<Output = Result<(), RukiniaError>> + Send + '_>> {
+        Box::pin(RukiniaProcess::run(self))
+    }
+
+    fn get_result(&self) -> RukiniaResultEntry {
+        RukiniaProcess::get_result(self)
+    }
+
+    fn display_format(&self) -> String {
+        RukiniaProcess::display_format(self)
+    }
 }
 
 impl FromStr for RukiniaAllTasks {
     type Err = ();
 
-    /// Convert from string representation to RukiniaAllTasks variant.
+    /// Find the task registered with this name.
     ///
     /// # Arguments
     /// * `s` - String representing the task name
     ///
     /// # Returns
-    /// `Result<Self, ()>` with the matching variant or error if no match found
+    /// `Result<Self, ()>` with the matching task or error if no task has this name
     ///
     /// # Examples
     /// ```rust,ignore
-    /// let task = RukiniaAllTasks::from_str("network_is_up").unwrap();
+    /// let task = RukiniaAllTasks::from_str("rukinia_netif_is_up").unwrap();
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            #[cfg(feature = "network")]
-            _ if s == RukiniaNetworkIsUp::get_rukinia_command() => Ok(RukiniaAllTasks::NetworkIsUp),
-            #[cfg(feature = "network")]
-            _ if s == RukiniaNetifHasIp::get_rukinia_command() => Ok(RukiniaAllTasks::NetifHasIp),
-            #[cfg(feature = "network")]
-            _ if s == RukiniaHttpReq::get_rukinia_command() => Ok(RukiniaAllTasks::HttpReq),
-            #[cfg(feature = "user")]
-            _ if s == RukiniaUser::get_rukinia_command() => Ok(RukiniaAllTasks::User),
-            #[cfg(feature = "user")]
-            _ if s == RukiniaGroup::get_rukinia_command() => Ok(RukiniaAllTasks::Group),
-            #[cfg(feature = "user")]
-            _ if s == RukiniaUserMemberOf::get_rukinia_command() => Ok(RukiniaAllTasks::UserMemberOf),
-            #[cfg(feature = "kernel")]
-            _ if s == RukiniaKernelConf::get_rukinia_command() => Ok(RukiniaAllTasks::KernelConf),
-            #[cfg(feature = "kernel")]
-            _ if s == RukiniaKernelMod::get_rukinia_command() => Ok(RukiniaAllTasks::KernelMod),
-            #[cfg(feature = "kernel")]
-            _ if s == RukiniaKernelThread::get_rukinia_command() => Ok(RukiniaAllTasks::KernelThread),
-            #[cfg(feature = "filesystem")]
-            _ if s == RukiniaSymlink::get_rukinia_command() => Ok(RukiniaAllTasks::Symlink),
-            #[cfg(feature = "filesystem")]
-            _ if s == RukiniaCmd::get_rukinia_command() => Ok(RukiniaAllTasks::Cmd),
-            _ if s == RukiniaTrue::get_rukinia_command() => Ok(RukiniaAllTasks::True),
-            _ if s == RukiniaFalse::get_rukinia_command() => Ok(RukiniaAllTasks::False),
-            _ => Err(()),
-        }
+        rukinia_find_task(s)
+            .map(|entry| RukiniaAllTasks { entry })
+            .ok_or(())
+    }
+}
+
+/// Checks the number of arguments given to a task.
+///
+/// # Arguments
+/// * `arguments` - The arguments given to the task
+/// * `min` - The minimum number of arguments
+/// * `max` - The maximum number of arguments, `None` if unbounded
+/// * `synopsis` - The arguments expected, e.g. `<link> <target>`
+///
+/// # Returns
+/// `Err` with a description of the problem if there are too few or too many arguments
+pub fn check_argument_count(
+    arguments: &[String],
+    min: usize,
+    max: Option<usize>,
+    synopsis: &str,
+) -> Result<(), String> {
+    if arguments.len() >= min && max.is_none_or(|max| arguments.len() <= max) {
+        return Ok(());
     }
+
+    let expected = if synopsis.is_empty() {
+        "no argument"
+    } else {
+        synopsis
+    };
+    let given = match arguments.len() {
+        0 => "no argument".to_string(),
+        1 => "1 argument".to_string(),
+        count => format!("{} arguments", count),
+    };
+    Err(format!("Expected {}, got {}", expected, given))
 }
 
-/// Trait defining synchronous Rukinia task processing.
+/// Trait defining Rukinia task processing.
 ///
-/// All synchronous tasks must implement this trait to be compatible with Rukinia's execution engine.
-pub trait RukiniaProcess {
+/// All tasks must implement this trait to be compatible with Rukinia's execution engine.
+/// Tasks are run asynchronously; those probing the system with blocking calls do it
+/// through `run_blocking`.
+pub trait RukiniaProcess: Send {
     /// Get the command string that identifies this task.
     fn get_rukinia_command() -> &'static str;
 
-    /// Create a new instance of the task.
+    /// Get the one line description of what the task checks, shown by `rukinia list-tasks`.
+    fn get_description() -> &'static str;
+
+    /// Get the arguments expected by the task, e.g. `<link> <target>`, empty if it takes none.
+    fn get_synopsis() -> &'static str {
+        ""
+    }
+
+    /// Check the arguments of the task without running it.
+    ///
+    /// # Returns
+    /// A description of the problem if the number or the shape of the arguments is invalid.
+    fn check_arguments(_arguments: &[String]) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Check the arguments given to `new`, see `check_arguments`.
+    ///
+    /// # Errors
+    /// Returns a `RukiniaError` describing the problem if the arguments are invalid.
+    fn validate_arguments(arguments: &[String]) -> Result<(), RukiniaError> {
+        Self::check_arguments(arguments).map_err(|message| {
+            RukiniaError::new(
+                format!("{} {}", Self::get_rukinia_command(), arguments.join(" ")),
+                "Invalid arguments".to_string(),
+                message,
+            )
+        })
+    }
+
+    /// Create a new instance of the task from its arguments, without running it.
     ///
     /// # Arguments
     /// * `arguments` - Vector of string arguments
     /// * `syntax` - Syntax rules to apply
+    ///
+    /// # Errors
+    /// Returns a `RukiniaError` if the arguments are invalid, see `validate_arguments`.
     fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
     where
         Self: Sized;
 
+    /// Asynchronously run the task, setting its result with the syntax applied.
+    ///
+    /// # Errors
+    /// Returns a `RukiniaError` if the system could not be probed.
+    fn run(&mut self) -> impl Future<Output = Result<(), RukiniaError>> + Send;
+
     /// Get the current result of the task.
     fn get_result(&self) -> RukiniaResultEntry;
 
@@ -224,81 +313,99 @@ pub trait RukiniaProcess {
 
     /// Apply syntax transformations to the result.
     ///
-    /// This handles NOT operators and AS label replacements.    
+    /// This handles NOT operators and AS label replacements, see `apply_syntax_to_result`.
     fn apply_syntax(&mut self) {
-        let mut new_rukinia_result_type: RukiniaResultType = self.get_result().result_type;
-        if self.get_syntax().contains_not() {
-            match self.get_result().result_type {
-                RukiniaResultType::TestSuccess => {
-                    new_rukinia_result_type = RukiniaResultType::TestFail
-                }
-                RukiniaResultType::TestFail => {
-                    new_rukinia_result_type = RukiniaResultType::TestSuccess
-                }
-            }
-        }
-        self.set_result(RukiniaResultEntry {
-            result_type: new_rukinia_result_type,
-            label: self
-                .get_syntax()
-                .get_as()
-                .unwrap_or_else(|| self.display_format()),
+        let result = apply_syntax_to_result(self.get_result(), &self.get_syntax(), || {
+            self.display_format()
         });
+        self.set_result(result);
     }
 }
 
-/// Trait defining asynchronous Rukinia task processing.
+/// Applies the `not` and `as` modifiers of `syntax` to the result of a task.
 ///
-/// All asynchronous tasks must implement this trait to be compatible with Rukinia's execution engine.
-pub trait RukiniaProcessAsync {
-    /// Get the command string that identifies this task.
-    fn get_rukinia_command() -> &'static str;
-
-    /// Asynchronously create a new instance of the task.
-    ///
-    /// # Arguments
-    /// * `arguments` - Vector of string arguments
-    /// * `syntax` - Syntax rules to apply    
-    fn async_new(
-        arguments: Vec<String>,
-        syntax: SyntaxForTrait,
-    ) -> impl Future<Output = Result<Self, RukiniaError>> + Send
-    where
-        Self: Sized;
-
-    /// Get the current result of the task.    
-    fn get_result(&self) -> RukiniaResultEntry;
-
-    /// Get the display format for the task's result.    
-    fn display_format(&self) -> String;
-
-    /// Set the result of the task.    
-    fn set_result(&mut self, result: RukiniaResultEntry);
+/// # Arguments
+/// * `result` - The result of the task, its type being inverted by `not` unless skipped
+/// * `syntax` - The syntax rules of the task
+/// * `display_format` - Gives the label of the result when there is no `as` modifier
+///
+/// # Returns
+/// The result with the syntax applied.
+pub fn apply_syntax_to_result(
+    result: RukiniaResultEntry,
+    syntax: &SyntaxForTrait,
+    display_format: impl FnOnce() -> String,
+) -> RukiniaResultEntry {
+    let mut result_type = result.result_type;
+    if syntax.contains_not() {
+        result_type = match result_type {
+            RukiniaResultType::TestSuccess => RukiniaResultType::TestFail,
+            RukiniaResultType::TestFail => RukiniaResultType::TestSuccess,
+            RukiniaResultType::TestSkip => RukiniaResultType::TestSkip,
+        };
+    }
+    RukiniaResultEntry {
+        result_type,
+        label: syntax.get_as().unwrap_or_else(display_format),
+        ..result
+    }
+}
 
-    /// Get the syntax rules for this task.    
-    fn get_syntax(&self) -> SyntaxForTrait;
+/// Runs the blocking `probe` of a task on the blocking threads of the runtime, then
+/// applies the syntax to its result.
+///
+/// Blocking tasks implement `RukiniaProcess::run` with it, so that reading files or
+/// walking `/proc` does not stall the other tests run by the runtime.
+///
+/// # Arguments
+/// * `task` - The task to run
+/// * `probe` - Probes the system and sets the result of the task, without applying the syntax
+///
+/// # Errors
+/// Returns the `RukiniaError` raised by `probe`, or one if it panicked.
+///
+/// # Examples
+/// ```rust,ignore
+/// async fn run(&mut self) -> Result<(), RukiniaError> {
+///     run_blocking(self, RukiniaUser::probe).await
+/// }
+/// ```
+pub async fn run_blocking<T>(
+    task: &mut T,
+    probe: fn(&mut T) -> Result<(), RukiniaError>,
+) -> Result<(), RukiniaError>
+where
+    T: RukiniaProcess + Clone + 'static,
+{
+    run_probe(task, T::get_rukinia_command(), probe).await?;
+    task.apply_syntax();
+    Ok(())
+}
 
-    /// Apply syntax transformations to the result.
-    ///
-    /// This handles NOT operators and AS label replacements.    
-    fn apply_syntax(&mut self) {
-        let mut new_rukinia_result_type: RukiniaResultType = self.get_result().result_type;
-        if self.get_syntax().contains_not() {
-            match self.get_result().result_type {
-                RukiniaResultType::TestSuccess => {
-                    new_rukinia_result_type = RukiniaResultType::TestFail
-                }
-                RukiniaResultType::TestFail => {
-                    new_rukinia_result_type = RukiniaResultType::TestSuccess
-                }
-            }
-        }
-        self.set_result(RukiniaResultEntry {
-            result_type: new_rukinia_result_type,
-            label: self
-                .get_syntax()
-                .get_as()
-                .unwrap_or_else(|| self.display_format()),
-        });
-    }
+/// Runs the blocking `probe` of a task on the blocking threads of the runtime, without
+/// applying the syntax, see `run_blocking`.
+///
+/// # Arguments
+/// * `task` - The task to run
+/// * `command` - The name of the task, given in the error raised if `probe` panicked
+/// * `probe` - Probes the system and sets the result of the task
+pub(crate) async fn run_probe<T>(
+    task: &mut T,
+    command: &str,
+    probe: fn(&mut T) -> Result<(), RukiniaError>,
+) -> Result<(), RukiniaError>
+where
+    T: Clone + Send + 'static,
+{
+    let mut probed = task.clone();
+    *task = tokio::task::spawn_blocking(move || probe(&mut probed).map(|_| probed))
+        .await
+        .map_err(|e| {
+            RukiniaError::new(
+                command.to_string(),
+                "Task panicked".to_string(),
+                e.to_string(),
+            )
+        })??;
+    Ok(())
 }
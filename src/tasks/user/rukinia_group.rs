@@ -3,39 +3,19 @@ use std::io::{BufRead, BufReader};
 
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
 
+#[derive(Clone)]
 pub struct RukiniaGroup {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
     result: RukiniaResultEntry,
 }
 
-impl RukiniaProcess for RukiniaGroup {
-    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
-    where
-        Self: Sized,
-    {
-        let mut rukinia_group = RukiniaGroup {
-            arguments,
-            syntax,
-            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
-
-        let group_name = match rukinia_group.arguments.first() {
-            Some(name) => name.trim_matches('"'),
-            None => {
-                return Err(RukiniaError::new(
-                    format!(
-                        "{} {}",
-                        RukiniaGroup::get_rukinia_command(),
-                        rukinia_group.arguments.join(" ")
-                    ),
-                    "Missing group name argument".to_string(),
-                    "No group name provided".to_string(),
-                ));
-            }
-        };
+impl RukiniaGroup {
+    /// Looks for the group in `/etc/group`.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
+        let group_name = self.arguments[0].as_str();
 
         let file = match File::open("/etc/group") {
             Ok(file) => file,
@@ -44,7 +24,7 @@ impl RukiniaProcess for RukiniaGroup {
                     format!(
                         "{} {}",
                         RukiniaGroup::get_rukinia_command(),
-                        rukinia_group.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "Failed to open /etc/group".to_string(),
                     err.to_string(),
@@ -58,7 +38,7 @@ impl RukiniaProcess for RukiniaGroup {
             match line {
                 Ok(line_content) => {
                     if line_content.starts_with(group_name) {
-                        rukinia_group.result.result_type = RukiniaResultType::TestSuccess;
+                        self.result.result_type = RukiniaResultType::TestSuccess;
                         break;
                     }
                 }
@@ -67,7 +47,7 @@ impl RukiniaProcess for RukiniaGroup {
                         format!(
                             "{} {}",
                             RukiniaGroup::get_rukinia_command(),
-                            rukinia_group.arguments.join(" ")
+                            self.arguments.join(" ")
                         ),
                         "Failed to read in /etc/group".to_string(),
                         err.to_string(),
@@ -76,14 +56,43 @@ impl RukiniaProcess for RukiniaGroup {
             }
         }
 
-        rukinia_group.apply_syntax();
-        Ok(rukinia_group)
+        Ok(())
+    }
+}
+
+impl RukiniaProcess for RukiniaGroup {
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    where
+        Self: Sized,
+    {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaGroup {
+            arguments,
+            syntax,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
     }
 
     fn get_rukinia_command() -> &'static str {
         "rukinia_group"
     }
 
+    fn get_description() -> &'static str {
+        "Checks a group exists"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<groupname>"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 1, Some(1), Self::get_synopsis())
+    }
+
     fn get_result(&self) -> RukiniaResultEntry {
         self.result.clone()
     }
@@ -3,43 +3,19 @@ use std::io::{BufRead, BufReader};
 
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
 
+#[derive(Clone)]
 pub struct RukiniaUser {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
     result: RukiniaResultEntry,
 }
 
-impl RukiniaProcess for RukiniaUser {
-    fn get_rukinia_command() -> &'static str {
-        "rukinia_user"
-    }
-
-    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
-    where
-        Self: Sized,
-    {
-        let mut rukinia_user = RukiniaUser {
-            arguments,
-            syntax,
-            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
-
-        let username = match rukinia_user.arguments.first() {
-            Some(name) => name.trim_matches('"'),
-            None => {
-                return Err(RukiniaError::new(
-                    format!(
-                        "{} {}",
-                        RukiniaUser::get_rukinia_command(),
-                        rukinia_user.arguments.join(" ")
-                    ),
-                    "Missing username argument".to_string(),
-                    "No username provided".to_string(),
-                ));
-            }
-        };
+impl RukiniaUser {
+    /// Looks for the user in `/etc/passwd`.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
+        let username = self.arguments[0].as_str();
 
         let file = match File::open("/etc/passwd") {
             Ok(file) => file,
@@ -48,7 +24,7 @@ impl RukiniaProcess for RukiniaUser {
                     format!(
                         "{} {}",
                         RukiniaUser::get_rukinia_command(),
-                        rukinia_user.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "Failed to open /etc/passwd".to_string(),
                     err.to_string(),
@@ -62,7 +38,7 @@ impl RukiniaProcess for RukiniaUser {
             match line {
                 Ok(line_content) => {
                     if line_content.starts_with(username) {
-                        rukinia_user.result.result_type = RukiniaResultType::TestSuccess;
+                        self.result.result_type = RukiniaResultType::TestSuccess;
                         break;
                     }
                 }
@@ -71,7 +47,7 @@ impl RukiniaProcess for RukiniaUser {
                         format!(
                             "{} {}",
                             RukiniaUser::get_rukinia_command(),
-                            rukinia_user.arguments.join(" ")
+                            self.arguments.join(" ")
                         ),
                         "Failed to read in /etc/passwd".to_string(),
                         err.to_string(),
@@ -80,8 +56,41 @@ impl RukiniaProcess for RukiniaUser {
             }
         }
 
-        rukinia_user.apply_syntax();
-        Ok(rukinia_user)
+        Ok(())
+    }
+}
+
+impl RukiniaProcess for RukiniaUser {
+    fn get_rukinia_command() -> &'static str {
+        "rukinia_user"
+    }
+
+    fn get_description() -> &'static str {
+        "Checks a user exists"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<username>"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 1, Some(1), Self::get_synopsis())
+    }
+
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    where
+        Self: Sized,
+    {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaUser {
+            arguments,
+            syntax,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
     }
 
     fn get_result(&self) -> RukiniaResultEntry {
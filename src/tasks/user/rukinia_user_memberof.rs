@@ -1,46 +1,22 @@
 use crate::core::rukinia_result::*;
 use crate::core::syntax::SyntaxForTrait;
-use crate::tasks::task::RukiniaProcess;
+use crate::tasks::task::{check_argument_count, run_blocking, RukiniaProcess};
 use nix::unistd::getgrouplist;
 use nix::unistd::Group;
 use nix::unistd::User;
 use std::collections::HashSet;
 use std::ffi::CString;
+#[derive(Clone)]
 pub struct RukiniaUserMemberOf {
     pub arguments: Vec<String>,
     pub syntax: SyntaxForTrait,
     result: RukiniaResultEntry,
 }
 
-impl RukiniaProcess for RukiniaUserMemberOf {
-    fn get_rukinia_command() -> &'static str {
-        "rukinia_user_memberof"
-    }
-
-    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
-    where
-        Self: Sized,
-    {
-        let mut rukinia_user_member_of = RukiniaUserMemberOf {
-            arguments,
-            syntax,
-            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
-        };
-
-        let username = match rukinia_user_member_of.arguments.first() {
-            Some(name) => name.trim_matches('"'),
-            None => {
-                return Err(RukiniaError::new(
-                    format!(
-                        "{} {}",
-                        RukiniaUserMemberOf::get_rukinia_command(),
-                        rukinia_user_member_of.arguments.join(" ")
-                    ),
-                    "Missing username argument".to_string(),
-                    "No username provided".to_string(),
-                ));
-            }
-        };
+impl RukiniaUserMemberOf {
+    /// Looks up the groups of the user in the user database.
+    fn probe(&mut self) -> Result<(), RukiniaError> {
+        let username = self.arguments[0].as_str();
 
         let mut user_groups = HashSet::new();
 
@@ -51,7 +27,7 @@ impl RukiniaProcess for RukiniaUserMemberOf {
                     format!(
                         "{} {}",
                         RukiniaUserMemberOf::get_rukinia_command(),
-                        rukinia_user_member_of.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "Failed to convert username to CString".to_string(),
                     err.to_string(),
@@ -66,7 +42,7 @@ impl RukiniaProcess for RukiniaUserMemberOf {
                     format!(
                         "{} {}",
                         RukiniaUserMemberOf::get_rukinia_command(),
-                        rukinia_user_member_of.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "User not found".to_string(),
                     format!("Failed to give user {}", username),
@@ -77,7 +53,7 @@ impl RukiniaProcess for RukiniaUserMemberOf {
                     format!(
                         "{} {}",
                         RukiniaUserMemberOf::get_rukinia_command(),
-                        rukinia_user_member_of.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     "Failed to retrieve user information".to_string(),
                     err.to_string(),
@@ -96,7 +72,7 @@ impl RukiniaProcess for RukiniaUserMemberOf {
                     format!(
                         "{} {}",
                         RukiniaUserMemberOf::get_rukinia_command(),
-                        rukinia_user_member_of.arguments.join(" ")
+                        self.arguments.join(" ")
                     ),
                     format!("Failed to retrieve group list for user {}", username),
                     err.to_string(),
@@ -104,18 +80,51 @@ impl RukiniaProcess for RukiniaUserMemberOf {
             }
         }
 
-        rukinia_user_member_of.result.result_type = RukiniaResultType::TestSuccess;
-        for group_name in rukinia_user_member_of.arguments.iter() {
+        self.result.result_type = RukiniaResultType::TestSuccess;
+        for group_name in self.arguments.iter() {
             if let Ok(Some(group)) = Group::from_name(group_name) {
                 if user_groups.contains(&group.gid) {
                     continue;
                 }
             }
-            rukinia_user_member_of.result.result_type = RukiniaResultType::TestFail;
+            self.result.result_type = RukiniaResultType::TestFail;
             break;
         }
-        rukinia_user_member_of.apply_syntax();
-        Ok(rukinia_user_member_of)
+        Ok(())
+    }
+}
+
+impl RukiniaProcess for RukiniaUserMemberOf {
+    fn get_rukinia_command() -> &'static str {
+        "rukinia_user_memberof"
+    }
+
+    fn get_description() -> &'static str {
+        "Checks a user is a member of groups"
+    }
+
+    fn get_synopsis() -> &'static str {
+        "<username> <group...>"
+    }
+
+    fn check_arguments(arguments: &[String]) -> Result<(), String> {
+        check_argument_count(arguments, 2, None, Self::get_synopsis())
+    }
+
+    fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError>
+    where
+        Self: Sized,
+    {
+        Self::validate_arguments(&arguments)?;
+        Ok(RukiniaUserMemberOf {
+            arguments,
+            syntax,
+            result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+        })
+    }
+
+    async fn run(&mut self) -> Result<(), RukiniaError> {
+        run_blocking(self, Self::probe).await
     }
 
     fn get_result(&self) -> RukiniaResultEntry {
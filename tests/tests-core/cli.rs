@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod cli {
+
+    use std::fs;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Output, Stdio};
+
+    /// Creates a directory holding a settings file and a test plan.
+    fn workspace(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("rukinia-cli-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("s.toml"), "[plan]\npaths = [\"p.conf\"]\n").unwrap();
+        fs::write(directory.join("p.conf"), "as \"always\" true\n").unwrap();
+        directory
+    }
+
+    /// Runs rukinia in `directory` with `args`, `input` being written to its stdin.
+    fn rukinia(directory: &Path, args: &[&str], input: &str) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rukinia"))
+            .args(args)
+            .current_dir(directory)
+            .env("XDG_CONFIG_HOME", directory)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn test_global_options_before_commands() {
+        let directory = workspace("global");
+        let global = ["--settings", "s.toml", "--color", "never"];
+        let run = |args: &[&str], input: &str| {
+            let args: Vec<&str> = global.iter().chain(args).copied().collect();
+            let output = rukinia(&directory, &args, input);
+            let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+            assert!(!stdout.contains('\x1b'), "{}", stdout);
+            (output.status.code(), stdout)
+        };
+
+        for args in [&["run", "p.conf"][..], &["run"], &["p.conf"], &[]] {
+            let (code, stdout) = run(args, "");
+            assert_eq!(code, Some(0), "{:?}: {}", args, stdout);
+            assert!(stdout.contains("[PASS] : always"), "{:?}: {}", args, stdout);
+            assert!(stdout.contains("Time spent"));
+        }
+
+        let (code, stdout) = run(&["check", "p.conf"], "");
+        assert_eq!(code, Some(0));
+        assert!(stdout.contains("1 test plan(s) checked, 0 problem(s) found"));
+        assert!(!stdout.contains("Time spent"));
+
+        let (code, stdout) = run(&["shell"], "exit\n");
+        assert_eq!(code, Some(0));
+        assert!(stdout.contains("Welcome to the Rukinia Shell!"));
+        assert!(!stdout.contains("Time spent"));
+
+        let (code, stdout) = run(&["list-tasks"], "");
+        assert_eq!(code, Some(0));
+        assert!(stdout.contains("rukinia_cmd <command>"));
+
+        let (code, stdout) = run(&["--dry-run"], "");
+        assert_eq!(code, Some(0));
+        assert!(stdout.contains("=> always"));
+
+        let output = rukinia(
+            &directory,
+            &["--settings=s.toml", "--color=never", "check"],
+            "",
+        );
+        assert_eq!(output.status.code(), Some(0));
+
+        let output = rukinia(&directory, &["-o", "junit", "check", "p.conf"], "");
+        assert_eq!(output.status.code(), Some(3));
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("cannot be used with the 'check' command"));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_configuration_errors() {
+        let directory = workspace("errors");
+        fs::write(directory.join("unbalanced.conf"), "if true\n").unwrap();
+        fs::write(
+            directory.join("invalid.toml"),
+            "[output]\nconsole = \"html\"\n",
+        )
+        .unwrap();
+
+        for args in [
+            &["run", "missing.conf"][..],
+            &["run", "unbalanced.conf"],
+            &["run", "--dry-run", "unbalanced.conf"],
+            &["-o", "junit=r.xml", "-o", "text=r.xml", "p.conf"],
+            &["--settings", "invalid.toml", "p.conf"],
+        ] {
+            let output = rukinia(&directory, args, "");
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            assert_eq!(output.status.code(), Some(3), "{:?}: {}", args, stdout);
+            assert!(!stdout.contains("Time spent"), "{:?}: {}", args, stdout);
+        }
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_color_mode() {
+        let directory = workspace("color");
+        fs::write(
+            directory.join("invalid.toml"),
+            "[output]\nconsole = \"html\"\n",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("always.toml"),
+            "[output]\ncolor = \"always\"\n",
+        )
+        .unwrap();
+        let stdout = |args: &[&str]| {
+            let output = rukinia(&directory, args, "");
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        };
+
+        let errors = stdout(&["--color", "always", "--settings", "invalid.toml", "p.conf"]);
+        assert!(errors.contains("\x1b[31m"), "{}", errors);
+        let errors = stdout(&["--color", "never", "--settings", "invalid.toml", "p.conf"]);
+        assert!(errors.contains("Failed to load settings"));
+        assert!(!errors.contains('\x1b'), "{}", errors);
+        // Auto mode, stdout not being a terminal
+        assert!(!stdout(&["--settings", "invalid.toml", "p.conf"]).contains('\x1b'));
+
+        assert!(stdout(&["--settings", "always.toml", "p.conf"]).contains('\x1b'));
+        let results = stdout(&["--settings", "always.toml", "--color", "auto", "p.conf"]);
+        assert!(!results.contains('\x1b'), "{}", results);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
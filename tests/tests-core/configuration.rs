@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod configuration {
+
+    use rukinia::core::configuration::{
+        rukinia_expand_test_plan_paths, rukinia_load_settings, rukinia_load_test_plans,
+        RukiniaConfig, RukiniaSettingsSources, RuntimeFlavor, DEFAULT_TEST_PLAN,
+    };
+    use rukinia::core::rukinia_result::ColorMode;
+    use rukinia::core::save_test_result::{ConsoleOutput, FormatOutput, ResultFormat};
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[test]
+    fn test_expand_test_plan_paths() {
+        assert_eq!(
+            rukinia_expand_test_plan_paths(&[]).unwrap(),
+            vec![DEFAULT_TEST_PLAN.to_string()]
+        );
+
+        let directory = std::env::temp_dir().join(format!("rukinia.d-{}", std::process::id()));
+        fs::create_dir_all(directory.join("nested.conf")).unwrap();
+        fs::write(directory.join("20-network.conf"), "true\n").unwrap();
+        fs::write(directory.join("10-users.conf"), "false\n").unwrap();
+        fs::write(directory.join("README"), "not a test plan\n").unwrap();
+        let directory_path = directory.to_string_lossy().into_owned();
+
+        let paths = rukinia_expand_test_plan_paths(&[
+            "-".to_string(),
+            directory_path.clone(),
+            "extra.conf".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                "-".to_string(),
+                directory
+                    .join("10-users.conf")
+                    .to_string_lossy()
+                    .into_owned(),
+                directory
+                    .join("20-network.conf")
+                    .to_string_lossy()
+                    .into_owned(),
+                "extra.conf".to_string(),
+            ]
+        );
+
+        let plans = rukinia_load_test_plans(&[directory_path]).unwrap();
+        assert_eq!(plans.len(), 2);
+        assert_eq!(plans[0].contents, "false\n");
+        assert_eq!(plans[1].contents, "true\n");
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_load_test_plans_error() {
+        let error =
+            rukinia_load_test_plans(&["/nonexistent/rukinia/plan.conf".to_string()]).unwrap_err();
+        assert_eq!(
+            error.system_error,
+            Some("Failed to open /nonexistent/rukinia/plan.conf".to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_settings() {
+        let directory =
+            std::env::temp_dir().join(format!("rukinia-settings-{}", std::process::id()));
+        fs::create_dir_all(directory.join("etc")).unwrap();
+        fs::create_dir_all(directory.join("home/rukinia")).unwrap();
+        fs::write(
+            directory.join("etc/config.toml"),
+            "[tokio]\nworker_threads = 2\n[output]\npath = \"system\"\n",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("home/rukinia/config.toml"),
+            "[tokio]\nworker_threads = 3\n",
+        )
+        .unwrap();
+        let mut sources = RukiniaSettingsSources {
+            system_dir: directory.join("etc"),
+            config_home: Some(directory.join("home")),
+            cli_path: Some(PathBuf::from("custom.toml")),
+            environment: HashMap::new(),
+        };
+
+        assert_eq!(
+            sources.get_paths(),
+            vec![
+                directory.join("etc/config.toml"),
+                directory.join("home/rukinia/config.toml"),
+                PathBuf::from("custom.toml"),
+            ]
+        );
+
+        let settings_path = directory.join("settings.toml");
+        fs::write(
+            &settings_path,
+            "[tokio]\n\
+             flavor = \"multi_thread\"\n\
+             worker_threads = 4\n\
+             [plan]\n\
+             paths = [\"/etc/rukinia/rukinia.d\"]\n\
+             [output]\n\
+             format = \"junit\"\n\
+             console = \"tap\"\n\
+             color = \"never\"\n\
+             [[output.reports]]\n\
+             format = \"json\"\n\
+             path = \"archive.json\"\n\
+             pretty = false\n\
+             [tasks.http_request]\n\
+             timeout = 5\n",
+        )
+        .unwrap();
+
+        sources.cli_path = None;
+        let settings = rukinia_load_settings(&sources).unwrap();
+        assert_eq!(settings.tokio.worker_threads, Some(3));
+        assert_eq!(settings.output.path, "system");
+
+        sources.cli_path = Some(settings_path.clone());
+        let settings = rukinia_load_settings(&sources).unwrap();
+        assert_eq!(settings.tokio.flavor, RuntimeFlavor::MultiThread);
+        assert_eq!(settings.tokio.worker_threads, Some(4));
+        assert_eq!(
+            settings.plan.paths,
+            vec!["/etc/rukinia/rukinia.d".to_string()]
+        );
+        assert_eq!(settings.output.format, Some(FormatOutput::JUnit));
+        assert_eq!(settings.output.path, "system");
+        let reports = settings.output.get_reports();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(
+            reports[0],
+            ResultFormat::new(FormatOutput::JUnit, "system.xml".to_string())
+        );
+        assert_eq!(reports[1].format, FormatOutput::Json);
+        assert_eq!(reports[1].path, "archive.json");
+        assert!(!reports[1].options.pretty);
+        assert_eq!(reports[1].options.suite_name, "rukinia");
+        assert_eq!(settings.output.console, ConsoleOutput::Tap);
+        assert_eq!(settings.output.color, Some(ColorMode::Never));
+        assert_eq!(
+            settings.tasks.http_request.get_timeout(),
+            Duration::from_secs(5)
+        );
+
+        sources.environment = HashMap::from([
+            ("RUKINIA_TOKIO__WORKER_THREADS".to_string(), "8".to_string()),
+            (
+                "RUKINIA_PLAN__PATHS".to_string(),
+                "a.conf,b.conf".to_string(),
+            ),
+        ]);
+        let settings = rukinia_load_settings(&sources).unwrap();
+        assert_eq!(settings.tokio.worker_threads, Some(8));
+        assert_eq!(
+            settings.plan.paths,
+            vec!["a.conf".to_string(), "b.conf".to_string()]
+        );
+
+        let defaults = RukiniaConfig::default();
+        assert_eq!(defaults.tokio.flavor, RuntimeFlavor::CurrentThread);
+        assert_eq!(defaults.plan.paths, vec![DEFAULT_TEST_PLAN.to_string()]);
+        assert_eq!(defaults.output.color, None);
+        let missing = RukiniaSettingsSources {
+            cli_path: Some(PathBuf::from("/nonexistent/rukinia/config.toml")),
+            ..RukiniaSettingsSources::default()
+        };
+        assert!(rukinia_load_settings(&missing).is_err());
+
+        fs::write(&settings_path, "[output]\nconsole = \"html\"\n").unwrap();
+        assert!(rukinia_load_settings(&sources).is_err());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod lexer {
+
+    use rukinia::core::lexer::{tokenize, TokenKind};
+
+    fn kinds(expression: &str) -> Vec<TokenKind> {
+        tokenize(expression)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_lexer_words_and_parentheses() {
+        assert_eq!(
+            kinds("not (rukinia_user root)"),
+            vec![
+                TokenKind::Word("not".to_string()),
+                TokenKind::LeftParen,
+                TokenKind::Word("rukinia_user".to_string()),
+                TokenKind::Word("root".to_string()),
+                TokenKind::RightParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_quotes() {
+        assert_eq!(
+            kinds(r#"rukinia_cmd sh -c "echo (hi) \"there\"" 'a\b'"#),
+            vec![
+                TokenKind::Word("rukinia_cmd".to_string()),
+                TokenKind::Word("sh".to_string()),
+                TokenKind::Word("-c".to_string()),
+                TokenKind::Quoted("echo (hi) \"there\"".to_string()),
+                TokenKind::Quoted("a\\b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_joined_parts() {
+        assert_eq!(
+            kinds(r#"--name="eth 0" an\ d"#),
+            vec![
+                TokenKind::Quoted("--name=eth 0".to_string()),
+                TokenKind::Quoted("an d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_columns() {
+        let tokens = tokenize("as \"x\" (true)").unwrap();
+        let columns: Vec<usize> = tokens.iter().map(|token| token.column).collect();
+        assert_eq!(columns, vec![1, 4, 8, 9, 13]);
+    }
+
+    #[test]
+    fn test_lexer_unterminated_quote() {
+        assert!(tokenize("rukinia_user \"root").is_err());
+        assert!(tokenize("rukinia_user 'root").is_err());
+        assert!(tokenize("rukinia_user root\\").is_err());
+    }
+}
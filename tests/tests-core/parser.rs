@@ -0,0 +1,203 @@
+#[cfg(test)]
+mod parser {
+
+    use rukinia::core::operator::LogicalOperator;
+    use rukinia::core::parser::{parse_expression, parse_expression_with_columns, Expression};
+    use rukinia::core::syntax::{ConditionKind, Syntax, SyntaxForTrait};
+    use rukinia::tasks::task::RukiniaAllTasks;
+    use std::str::FromStr;
+
+    fn named(name: &str) -> RukiniaAllTasks {
+        RukiniaAllTasks::from_str(name).unwrap()
+    }
+
+    fn task(task: RukiniaAllTasks, syntax: Vec<Syntax>) -> Expression {
+        Expression::Task {
+            task,
+            arguments: Vec::new(),
+            syntax: SyntaxForTrait::Arguments(syntax),
+        }
+    }
+
+    #[test]
+    fn test_parser_single_task() {
+        assert_eq!(
+            parse_expression("not as \"label\" id check true").unwrap(),
+            task(
+                named("true"),
+                vec![
+                    Syntax::Not,
+                    Syntax::As("label".to_string()),
+                    Syntax::Id("check".to_string())
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parser_left_to_right() {
+        assert_eq!(
+            parse_expression("true or false and true").unwrap(),
+            Expression::Logical {
+                operator: LogicalOperator::And,
+                left: Box::new(Expression::Logical {
+                    operator: LogicalOperator::Or,
+                    left: Box::new(task(named("true"), Vec::new())),
+                    right: Box::new(task(named("false"), Vec::new())),
+                }),
+                right: Box::new(task(named("true"), Vec::new())),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parser_group() {
+        assert_eq!(
+            parse_expression("not ((true) and false)").unwrap(),
+            Expression::Group {
+                expression: Box::new(Expression::Logical {
+                    operator: LogicalOperator::And,
+                    left: Box::new(Expression::Group {
+                        expression: Box::new(task(named("true"), Vec::new())),
+                        syntax: SyntaxForTrait::Arguments(Vec::new()),
+                    }),
+                    right: Box::new(task(named("false"), Vec::new())),
+                }),
+                syntax: SyntaxForTrait::Arguments(vec![Syntax::Not]),
+            }
+        );
+    }
+
+    #[cfg(feature = "filesystem")]
+    #[test]
+    fn test_parser_quoted_arguments() {
+        assert_eq!(
+            parse_expression(r#"rukinia_cmd sh -c "echo (hi)" "and" and true"#).unwrap(),
+            Expression::Logical {
+                operator: LogicalOperator::And,
+                left: Box::new(Expression::Task {
+                    task: named("rukinia_cmd"),
+                    arguments: vec![
+                        "sh".to_string(),
+                        "-c".to_string(),
+                        "echo (hi)".to_string(),
+                        "and".to_string()
+                    ],
+                    syntax: SyntaxForTrait::Arguments(Vec::new()),
+                }),
+                right: Box::new(task(named("true"), Vec::new())),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parser_errors() {
+        assert!(parse_expression("").is_err());
+        assert!(parse_expression("rukinia_unknown root").is_err());
+        assert!(parse_expression("(true").is_err());
+        assert!(parse_expression("true)").is_err());
+        assert!(parse_expression("true and").is_err());
+        assert!(parse_expression("as").is_err());
+        assert!(parse_expression("true (false)").is_err());
+    }
+
+    #[test]
+    fn test_parser_conditions() {
+        let group = |expression| Expression::Group {
+            expression: Box::new(expression),
+            syntax: SyntaxForTrait::Arguments(Vec::new()),
+        };
+        assert_eq!(
+            parse_expression("skip_if (true) only_if (not false) id t true").unwrap(),
+            Expression::Conditional {
+                kind: ConditionKind::SkipIf,
+                condition: Box::new(group(task(named("true"), Vec::new()))),
+                expression: Box::new(Expression::Conditional {
+                    kind: ConditionKind::OnlyIf,
+                    condition: Box::new(group(task(named("false"), vec![Syntax::Not]))),
+                    expression: Box::new(task(named("true"), vec![Syntax::Id("t".to_string())])),
+                }),
+            }
+        );
+
+        let (tree, columns) =
+            parse_expression_with_columns("only_if (false x) as \"y\" true").unwrap();
+        assert_eq!(columns.tasks, vec![10, 26]);
+        assert_eq!(tree.get_tasks().len(), 2);
+        assert_eq!(tree.get_as(), Some("y".to_string()));
+
+        let error = |expression| {
+            parse_expression_with_columns(expression)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("skip_if true false"),
+            "Unexpected 'true' at column 9, expected skip_if (<condition>) <test>"
+        );
+        assert_eq!(
+            error("only_if"),
+            "Missing condition for 'only_if' at column 8"
+        );
+        assert_eq!(
+            error("skip_if (id x true) false"),
+            "A condition of 'skip_if' cannot have an id at column 13"
+        );
+        assert_eq!(error("skip_if (true)"), "No task in test at column 15");
+        assert_eq!(
+            error("true and skip_if (true) false"),
+            "Unknown task 'skip_if' at column 10"
+        );
+    }
+
+    #[test]
+    fn test_parser_id_and_label() {
+        let tree = parse_expression("id root as \"root user\" rukinia_user root").unwrap();
+        assert_eq!(tree.get_id(), Some("root".to_string()));
+        assert_eq!(tree.get_as(), Some("root user".to_string()));
+
+        let tree = parse_expression("true and id second (false)").unwrap();
+        assert_eq!(tree.get_id(), Some("second".to_string()));
+        assert_eq!(tree.get_as(), None);
+
+        let tree = parse_expression("id first true and id second (false)").unwrap();
+        assert_eq!(
+            tree.get_ids(),
+            vec!["first".to_string(), "second".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parser_columns() {
+        let (tree, columns) =
+            parse_expression_with_columns("id a true and (not id b false x)").unwrap();
+        assert_eq!(columns.tasks, vec![6, 25]);
+        assert_eq!(columns.ids, vec![4, 23]);
+        let tasks: Vec<_> = tree
+            .get_tasks()
+            .into_iter()
+            .map(|(task, arguments)| (task.clone(), arguments.to_vec()))
+            .collect();
+        assert_eq!(
+            tasks,
+            vec![
+                (named("true"), Vec::new()),
+                (named("false"), vec!["x".to_string()]),
+            ]
+        );
+
+        let error = parse_expression_with_columns("true and (false").unwrap_err();
+        assert_eq!(error.column, 10);
+        assert_eq!(error.message, "Missing ')' to close '('");
+
+        let error = parse_expression_with_columns("(true false)) or true").unwrap_err();
+        assert_eq!(error.column, 13);
+        assert_eq!(error.to_string(), "Unexpected ')' at column 13");
+        assert_eq!(
+            parse_expression("(true or )")
+                .unwrap_err()
+                .system_error_message,
+            "Unexpected ')' at column 10, expected a task"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+#[cfg(test)]
+mod plan {
+
+    use rukinia::core::plan::{
+        rukinia_check_blocks, rukinia_find_block_problems, rukinia_plan_lines,
+        rukinia_read_plan_lines, RukiniaPlanLine, RukiniaPlanStatement,
+    };
+    use std::fs;
+    use std::path::Path;
+
+    fn lines(contents: &str) -> Vec<(usize, String)> {
+        rukinia_plan_lines("a.conf", contents)
+            .into_iter()
+            .map(|RukiniaPlanLine { line, text, .. }| (line, text))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_comments_and_blank_lines() {
+        assert_eq!(
+            lines("# Users\n\n   \nrukinia_user root # admin\n\t# indented comment\ntrue#not a comment\n"),
+            vec![
+                (4, "rukinia_user root".to_string()),
+                (6, "true#not a comment".to_string()),
+            ]
+        );
+        assert_eq!(
+            lines(r##"rukinia_cmd echo "# kept" '#kept' \# http://host/#top (#)"##),
+            vec![(
+                1,
+                r##"rukinia_cmd echo "# kept" '#kept' \# http://host/#top ("##.to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_plan_continuation_lines() {
+        assert_eq!(
+            lines("true \\\n  and false\\\n\n# comment\nnot true \\\n\t or false # or true\n"),
+            vec![
+                (1, "true and false".to_string()),
+                (5, "not true or false".to_string()),
+            ]
+        );
+        assert_eq!(
+            lines("rukinia_cmd echo \\\\\ntrue # not continued \\\nfalse \\"),
+            vec![
+                (1, "rukinia_cmd echo \\\\".to_string()),
+                (2, "true".to_string()),
+                (3, "false".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_set_statements() {
+        let statements: Vec<_> = rukinia_plan_lines(
+            "a.conf",
+            "set IFACE eth0\nset LABEL \"${IFACE} is up\"\nsettle\n",
+        )
+        .iter()
+        .map(|line| line.parse())
+        .collect();
+        assert_eq!(
+            statements[0],
+            Ok(RukiniaPlanStatement::Set {
+                name: "IFACE".to_string(),
+                value: "eth0".to_string(),
+            })
+        );
+        assert_eq!(
+            statements[1],
+            Ok(RukiniaPlanStatement::Set {
+                name: "LABEL".to_string(),
+                value: "${IFACE} is up".to_string(),
+            })
+        );
+        assert!(statements[2].is_err());
+
+        let error = |text: &str| {
+            let line = RukiniaPlanLine {
+                path: "a.conf".to_string(),
+                line: 1,
+                text: text.to_string(),
+            };
+            let error = line.parse().unwrap_err();
+            format!("{}: {}", error.column, error.message)
+        };
+        assert_eq!(error("set 1X eth0"), "5: Invalid variable name '1X'");
+        assert_eq!(error("set IFACE eth0 eth1"), "16: Unexpected 'eth1'");
+        assert_eq!(error("set IFACE"), "10: Missing value for 'set'");
+        assert_eq!(error("set IFACE ("), "11: Missing value for 'set'");
+    }
+
+    #[test]
+    fn test_plan_blocks() {
+        let line = |text: &str| RukiniaPlanLine {
+            path: "a.conf".to_string(),
+            line: 1,
+            text: text.to_string(),
+        };
+        match line("if  rukinia_kmod wl18xx and true").parse() {
+            Ok(RukiniaPlanStatement::If(expression, columns)) => {
+                assert_eq!(expression.get_tasks().len(), 2);
+                assert_eq!(columns.tasks, vec![5, 29]);
+            }
+            other => panic!("unexpected statement {:?}", other),
+        }
+        assert_eq!(line("else").parse(), Ok(RukiniaPlanStatement::Else));
+        assert_eq!(line("end").parse(), Ok(RukiniaPlanStatement::End));
+        assert_eq!(line("if true").get_block_keyword(), Some("if"));
+        assert_eq!(line("iffy true").get_block_keyword(), None);
+
+        let error = |text: &str| {
+            let error = line(text).parse().unwrap_err();
+            format!("{}: {}", error.column, error.message)
+        };
+        assert_eq!(error("if"), "3: Missing test for 'if'");
+        assert_eq!(error("if true and"), "12: No task in test");
+        assert_eq!(
+            error("if id a true"),
+            "7: The test of 'if' cannot have an id"
+        );
+        assert_eq!(error("end true"), "5: Unexpected 'true'");
+
+        let problems = |contents: &str| -> Vec<String> {
+            let lines = rukinia_plan_lines("a.conf", contents);
+            rukinia_find_block_problems(&lines)
+                .into_iter()
+                .map(|(index, error)| format!("{}: {}", lines[index].line, error.message))
+                .collect()
+        };
+        assert!(problems("if true\nif false\nelse\nend\nend\n").is_empty());
+        assert!(problems("if true\nelse false\nend\n").is_empty());
+        assert_eq!(problems("true\nelse\n"), vec!["2: 'else' without 'if'"]);
+        assert_eq!(
+            problems("if true\nend\nend\n"),
+            vec!["3: 'end' without 'if'"]
+        );
+        assert_eq!(
+            problems("if true\nelse\nelse\nend\n"),
+            vec!["3: Several 'else' for the 'if' at a.conf:1"]
+        );
+        assert_eq!(
+            problems("if true\nif false\nend\nelse\nelse\n"),
+            vec![
+                "1: Missing 'end' to close the 'if'",
+                "5: Several 'else' for the 'if' at a.conf:1"
+            ]
+        );
+
+        let lines = rukinia_plan_lines("a.conf", "true\nend\n");
+        let error = rukinia_check_blocks(&lines).unwrap_err();
+        assert_eq!(error.test_command, "end");
+        assert_eq!(error.system_error.as_deref(), Some("Invalid block"));
+        assert_eq!(error.system_error_message, "a.conf:2: 'end' without 'if'");
+    }
+
+    /// Returns the lines of the plan at `path` with their location.
+    fn read(path: &Path) -> Vec<String> {
+        let path = path.to_string_lossy();
+        rukinia_read_plan_lines(&path, &fs::read_to_string(path.as_ref()).unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|line| format!("{} {}", line.get_location(), line.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_includes() {
+        let directory =
+            std::env::temp_dir().join(format!("rukinia-include-{}", std::process::id()));
+        fs::create_dir_all(directory.join("common")).unwrap();
+        fs::create_dir_all(directory.join("soc")).unwrap();
+        fs::write(
+            directory.join("board.conf"),
+            "include common/users.conf\ninclude \"soc/*.conf\" # SoC checks\ntrue\n",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("common/users.conf"),
+            "# Users\nid root rukinia_user root\n",
+        )
+        .unwrap();
+        fs::write(directory.join("soc/b.conf"), "false\n").unwrap();
+        fs::write(
+            directory.join("soc/a.conf"),
+            "include ../common/kmod.conf\n",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("common/kmod.conf"),
+            "\nrukinia_kmod i2c_hid\n",
+        )
+        .unwrap();
+
+        let root = directory.to_string_lossy();
+        assert_eq!(
+            read(&directory.join("board.conf")),
+            vec![
+                format!("{}/common/users.conf:2 id root rukinia_user root", root),
+                format!("{}/soc/../common/kmod.conf:2 rukinia_kmod i2c_hid", root),
+                format!("{}/soc/b.conf:1 false", root),
+                format!("{}/board.conf:3 true", root),
+            ]
+        );
+
+        fs::write(
+            directory.join("common/kmod.conf"),
+            "include ../soc/a.conf\n",
+        )
+        .unwrap();
+        let board = directory.join("board.conf").to_string_lossy().into_owned();
+        let error = rukinia_read_plan_lines(&board, "\ninclude soc/a.conf\n").unwrap_err();
+        assert_eq!(error.system_error.as_deref(), Some("Include cycle"));
+        assert_eq!(
+            error.system_error_message,
+            format!(
+                "{0}/soc/a.conf:1 -> {0}/soc/../common/kmod.conf:1 -> {0}/soc/../common/../soc/a.conf",
+                root
+            )
+        );
+
+        fs::write(directory.join("soc/a.conf"), "if true\nelse\n").unwrap();
+        fs::write(directory.join("soc/b.conf"), "end\n").unwrap();
+        let lines = rukinia_read_plan_lines(&board, "if false\ninclude soc/*.conf\nend\n").unwrap();
+        let problems: Vec<String> = rukinia_find_block_problems(&lines)
+            .into_iter()
+            .map(|(index, error)| format!("{} {}", lines[index].get_location(), error.message))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                format!("{}/soc/a.conf:1 Missing 'end' to close the 'if'", root),
+                format!("{}/soc/b.conf:1 'end' without 'if'", root),
+            ]
+        );
+
+        let error = rukinia_read_plan_lines(&board, "include board.conf").unwrap_err();
+        assert_eq!(error.system_error.as_deref(), Some("Include cycle"));
+
+        let error = rukinia_read_plan_lines(&board, "true\ninclude missing.conf").unwrap_err();
+        assert_eq!(error.test_command, "include missing.conf");
+        assert!(error
+            .system_error_message
+            .starts_with(&format!("{}:2: ", board)));
+
+        let error = rukinia_read_plan_lines(&board, "include soc/*.yaml").unwrap_err();
+        assert_eq!(
+            error.system_error_message,
+            format!("{}:1: No test plan matches {}/soc/*.yaml", board, root)
+        );
+
+        let error = rukinia_read_plan_lines(&board, "include a.conf b.conf").unwrap_err();
+        assert_eq!(
+            error.system_error_message,
+            format!("{}:1: Expected <path>, got 2 argument(s)", board)
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
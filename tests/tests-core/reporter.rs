@@ -0,0 +1,316 @@
+#[cfg(test)]
+mod reporter {
+
+    use rukinia::core::configuration::RukiniaTestPlan;
+    use rukinia::core::reporter::{
+        rukinia_report_reporter, Reporter, ReporterResult, RukiniaTestStart,
+    };
+    use rukinia::core::rukinia_result::{
+        RukiniaError, RukiniaResultEntry, RukiniaResultType, RukiniaRunInfo, RukiniaRunOutcome,
+    };
+    use rukinia::core::run_tasks::{rukinia_execute_all_tests, TestSelection};
+    use rukinia::core::save_test_result::{FormatOutput, ReportMode, ResultFormat};
+    use rukinia::core::variables::RukiniaVariables;
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    fn run(run_id: &str) -> RukiniaRunInfo {
+        RukiniaRunInfo {
+            run_id: run_id.to_string(),
+            start_time: UNIX_EPOCH + Duration::from_secs(1714657512),
+            hostname: "board".to_string(),
+            version: "0.1.0".to_string(),
+        }
+    }
+
+    /// Records the events it receives.
+    struct RecordingReporter {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl RecordingReporter {
+        fn record(&self, event: String) -> ReporterResult {
+            self.events.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    impl Reporter for RecordingReporter {
+        fn name(&self) -> String {
+            "recording".to_string()
+        }
+
+        fn on_run_start(&mut self, run: &RukiniaRunInfo) -> ReporterResult {
+            self.record(format!("run start {}", run.version))
+        }
+
+        fn on_plan_start(&mut self, plan: &RukiniaTestPlan) -> ReporterResult {
+            self.record(format!("plan start {}", plan.path))
+        }
+
+        fn on_test_start(&mut self, test: &RukiniaTestStart) -> ReporterResult {
+            self.record(format!(
+                "test start {}:{} {:?}",
+                test.plan,
+                test.line,
+                test.get_id()
+            ))
+        }
+
+        fn on_test_result(&mut self, entry: &RukiniaResultEntry) -> ReporterResult {
+            self.record(format!(
+                "result {} at {}",
+                entry.label,
+                entry.location.as_deref().unwrap_or_default()
+            ))
+        }
+
+        fn on_error(&mut self, error: &RukiniaError, _duration: Duration) -> ReporterResult {
+            self.record(format!(
+                "error {} at {}",
+                error.test_command,
+                error.get_location().unwrap_or_default()
+            ))
+        }
+
+        fn on_plan_end(&mut self, plan: &RukiniaTestPlan) -> ReporterResult {
+            self.record(format!("plan end {}", plan.path))
+        }
+
+        fn on_run_end(&mut self, outcome: &RukiniaRunOutcome) -> ReporterResult {
+            self.record(format!("run end {}", outcome.total()))
+        }
+    }
+
+    #[test]
+    fn test_reporter_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut reporters: Vec<Box<dyn Reporter>> = vec![Box::new(RecordingReporter {
+            events: events.clone(),
+        })];
+        let plans = vec![RukiniaTestPlan::new("a.conf", "id t true\nunknown_task").unwrap()];
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let outcome = runtime.block_on(rukinia_execute_all_tests(
+            &plans,
+            &mut reporters,
+            &TestSelection::default(),
+            &RukiniaVariables::default(),
+        ));
+        assert_eq!(outcome.passed, 1);
+        assert_eq!(outcome.errors, 1);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "run start 0.1.0",
+                "plan start a.conf",
+                "test start a.conf:1 Some(\"t\")",
+                "result true at a.conf:1",
+                "test start a.conf:2 None",
+                "error unknown_task at a.conf:2",
+                "plan end a.conf",
+                "run end 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reporter_blocks() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut reporters: Vec<Box<dyn Reporter>> = vec![Box::new(RecordingReporter {
+            events: events.clone(),
+        })];
+        let plans = vec![RukiniaTestPlan::new(
+            "a.conf",
+            &[
+                "if true",
+                "    if false",
+                "        set A x",
+                "        as \"${A}\" unknown_task",
+                "    else",
+                "        as \"nested\" true",
+                "    end",
+                "else",
+                "    if true",
+                "        as \"inactive\" true",
+                "    end",
+                "end",
+                "if unknown_task",
+                "    false",
+                "else",
+                "    false",
+                "end",
+                "as \"${A}\" true",
+            ]
+            .join("\n"),
+        )
+        .unwrap()];
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let outcome = runtime.block_on(rukinia_execute_all_tests(
+            &plans,
+            &mut reporters,
+            &TestSelection::default(),
+            &RukiniaVariables::default(),
+        ));
+        assert_eq!(outcome.passed, 1);
+        assert_eq!(outcome.errors, 2);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "run start 0.1.0",
+                "plan start a.conf",
+                "test start a.conf:6 None",
+                "result nested at a.conf:6",
+                "test start a.conf:13 None",
+                "error if unknown_task at a.conf:13",
+                "test start a.conf:18 None",
+                "error as \"${A}\" true at a.conf:18",
+                "plan end a.conf",
+                "run end 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reporter_malformed_blocks() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut reporters: Vec<Box<dyn Reporter>> = vec![Box::new(RecordingReporter {
+            events: events.clone(),
+        })];
+        let plans = vec![RukiniaTestPlan::new(
+            "a.conf",
+            &[
+                "if true",
+                "    as \"then\" true",
+                "else oops",
+                "    as \"else\" false",
+                "end",
+                "if false",
+                "    false",
+                "end extra",
+                "as \"after\" true",
+            ]
+            .join("\n"),
+        )
+        .unwrap()];
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let outcome = runtime.block_on(rukinia_execute_all_tests(
+            &plans,
+            &mut reporters,
+            &TestSelection::default(),
+            &RukiniaVariables::default(),
+        ));
+        assert_eq!(outcome.passed, 2);
+        assert_eq!(outcome.failed, 0);
+        assert_eq!(outcome.errors, 1);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "run start 0.1.0",
+                "plan start a.conf",
+                "test start a.conf:2 None",
+                "result then at a.conf:2",
+                "test start a.conf:3 None",
+                "error else oops at a.conf:3",
+                "test start a.conf:9 None",
+                "result after at a.conf:9",
+                "plan end a.conf",
+                "run end 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_reporter() {
+        let path =
+            std::env::temp_dir().join(format!("rukinia-reporter-{}.json", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let mut format = ResultFormat::new(FormatOutput::Json, path.clone());
+        format.options.pretty = false;
+
+        let mut reporter = rukinia_report_reporter(&format);
+        reporter.on_run_start(&run("r1")).unwrap();
+        reporter
+            .on_test_result(&RukiniaResultEntry::new(
+                RukiniaResultType::TestSuccess,
+                "true".to_string(),
+            ))
+            .unwrap();
+        reporter.on_run_end(&RukiniaRunOutcome::default()).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(contents.starts_with(
+            "{\"run\":{\"id\":\"r1\",\"start_time\":\"2024-05-02T13:45:12Z\",\
+             \"hostname\":\"board\",\"version\":\"0.1.0\"},\"summary\":{\"total\":1,"
+        ));
+    }
+
+    #[test]
+    fn test_report_modes() {
+        let directory = std::env::temp_dir().join(format!("rukinia-modes-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("rukinia.csv").to_string_lossy().into_owned();
+        let entry = RukiniaResultEntry::new(RukiniaResultType::TestSuccess, "true".to_string());
+        let write_run = |mode: ReportMode, run_id: &str| {
+            let mut format = ResultFormat::new(FormatOutput::Csv, path.clone());
+            format.options.mode = mode;
+            let mut reporter = rukinia_report_reporter(&format);
+            reporter.on_run_start(&run(run_id)).unwrap();
+            reporter.on_test_result(&entry).unwrap();
+            reporter.on_run_end(&RukiniaRunOutcome::default()).unwrap();
+        };
+
+        let header =
+            "ID,TEST MESSAGE,RESULT,DURATION (s),RUN ID,RUN START,HOSTNAME,RUKINIA VERSION\n";
+        let row = |run_id: &str| {
+            format!(
+                ",true,SUCCESS,0.000,{},2024-05-02T13:45:12Z,board,0.1.0\n",
+                run_id
+            )
+        };
+
+        write_run(ReportMode::Truncate, "r1");
+        write_run(ReportMode::Truncate, "r2");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}{}", header, row("r2"))
+        );
+
+        write_run(ReportMode::Append, "r3");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}{}{}", header, row("r2"), row("r3"))
+        );
+
+        write_run(ReportMode::Timestamped, "r4");
+        let timestamped = directory.join("rukinia-20240502T134512Z.csv");
+        assert_eq!(
+            fs::read_to_string(&timestamped).unwrap(),
+            format!("{}{}", header, row("r4"))
+        );
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_run_info() {
+        let run = RukiniaRunInfo::now();
+        assert!(run.start_time <= SystemTime::now());
+        assert_eq!(run.version, env!("CARGO_PKG_VERSION"));
+        assert!(!run.hostname.is_empty());
+        assert_ne!(run.run_id, RukiniaRunInfo::now().run_id);
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod rukinia_result {
+
+    use rukinia::core::rukinia_result::{
+        ColorMode, FailOn, RukiniaError, RukiniaExitCode, RukiniaResultEntry, RukiniaResultType,
+        RukiniaRunOutcome,
+    };
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    fn outcome(passed: usize, failed: usize, errors: usize) -> RukiniaRunOutcome {
+        RukiniaRunOutcome {
+            passed,
+            failed,
+            errors,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_run_outcome_counts() {
+        let mut run_outcome = RukiniaRunOutcome::default();
+        run_outcome.add_result(&RukiniaResultEntry::new(
+            RukiniaResultType::TestSuccess,
+            "pass".to_string(),
+        ));
+        run_outcome.add_result(&RukiniaResultEntry::new(
+            RukiniaResultType::TestFail,
+            "fail".to_string(),
+        ));
+        run_outcome.add_result(&RukiniaResultEntry::new(
+            RukiniaResultType::TestSkip,
+            "skip".to_string(),
+        ));
+        run_outcome.add_error(
+            &RukiniaError::new_with_short_description("error".to_string(), "error".to_string()),
+            Duration::ZERO,
+        );
+        assert_eq!(
+            (
+                run_outcome.passed,
+                run_outcome.failed,
+                run_outcome.skipped,
+                run_outcome.errors
+            ),
+            (1, 1, 1, 1)
+        );
+        assert_eq!(run_outcome.total(), 4);
+    }
+
+    #[test]
+    fn test_run_outcome_slowest() {
+        let mut run_outcome = RukiniaRunOutcome::default();
+        for (label, millis) in [("fast", 1), ("slow", 30), ("medium", 20)] {
+            let mut entry = RukiniaResultEntry::new(RukiniaResultType::TestSuccess, label.to_string());
+            entry.duration = Duration::from_millis(millis);
+            run_outcome.add_result(&entry);
+        }
+        let slowest: Vec<&str> = run_outcome
+            .slowest(2)
+            .iter()
+            .map(|timing| timing.label.as_str())
+            .collect();
+        assert_eq!(slowest, vec!["slow", "medium"]);
+        assert_eq!(run_outcome.duration, Duration::from_millis(51));
+    }
+
+    #[test]
+    fn test_run_outcome_fail_on_fail() {
+        assert_eq!(outcome(3, 0, 0).exit_code(FailOn::Fail), RukiniaExitCode::Success);
+        assert_eq!(outcome(3, 1, 0).exit_code(FailOn::Fail), RukiniaExitCode::TestFailed);
+        assert_eq!(outcome(3, 1, 1).exit_code(FailOn::Fail), RukiniaExitCode::SystemError);
+    }
+
+    #[test]
+    fn test_run_outcome_fail_on_error() {
+        assert_eq!(outcome(3, 1, 0).exit_code(FailOn::Error), RukiniaExitCode::Success);
+        assert_eq!(outcome(3, 1, 1).exit_code(FailOn::Error), RukiniaExitCode::SystemError);
+    }
+
+    #[test]
+    fn test_run_outcome_fail_on_never() {
+        assert_eq!(outcome(0, 1, 1).exit_code(FailOn::Never), RukiniaExitCode::Success);
+    }
+
+    #[test]
+    fn test_fail_on_from_str() {
+        assert_eq!(FailOn::from_str("never"), Ok(FailOn::Never));
+        assert!(FailOn::from_str("always").is_err());
+    }
+
+    #[test]
+    fn test_color_mode() {
+        assert_eq!(ColorMode::from_str("never"), Ok(ColorMode::Never));
+        assert!(ColorMode::from_str("sometimes").is_err());
+        assert!(ColorMode::Always.is_enabled());
+        assert!(!ColorMode::Never.is_enabled());
+    }
+}
@@ -0,0 +1,345 @@
+#[cfg(test)]
+mod run_tasks {
+
+    use rukinia::core::configuration::RukiniaTestPlan;
+    use rukinia::core::rukinia_result::RukiniaResultType;
+    use rukinia::core::run_tasks::{
+        rukinia_check_result_formats, rukinia_check_test_ids, rukinia_check_test_plans,
+        rukinia_describe_test_plans, rukinia_execute_single_test, rukinia_run_analysis,
+        TestSelection,
+    };
+    use rukinia::core::save_test_result::{FormatOutput, ReportMode, ResultFormat};
+    use rukinia::core::variables::RukiniaVariables;
+
+    fn plan(path: &str, contents: &str) -> Vec<RukiniaTestPlan> {
+        vec![RukiniaTestPlan::new(path, contents).unwrap()]
+    }
+
+    #[test]
+    fn test_test_selection() {
+        let all = TestSelection::default();
+        assert!(all.is_selected(None));
+        assert!(all.is_selected(Some("root")));
+
+        let only = TestSelection {
+            only: vec!["root".to_string()],
+            exclude: Vec::new(),
+        };
+        assert!(only.is_selected(Some("root")));
+        assert!(!only.is_selected(Some("eth0")));
+        assert!(!only.is_selected(None));
+
+        let exclude = TestSelection {
+            only: Vec::new(),
+            exclude: vec!["root".to_string()],
+        };
+        assert!(!exclude.is_selected(Some("root")));
+        assert!(exclude.is_selected(Some("eth0")));
+        assert!(exclude.is_selected(None));
+    }
+
+    #[test]
+    fn test_check_test_ids() {
+        let plans = plan(
+            "a.conf",
+            "id root rukinia_user root\nrukinia_group root\nid eth0 rukinia_netif_is_up eth0\n",
+        );
+        assert!(rukinia_check_test_ids(&plans, &TestSelection::default()).is_ok());
+        assert!(rukinia_check_test_ids(
+            &plans,
+            &TestSelection {
+                only: vec!["eth0".to_string()],
+                exclude: vec!["root".to_string()],
+            }
+        )
+        .is_ok());
+
+        let error = rukinia_check_test_ids(
+            &plans,
+            &TestSelection {
+                only: vec!["unknown".to_string()],
+                exclude: Vec::new(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error.system_error_message, "Unknown test id 'unknown'");
+
+        let mut plans = plan("a.conf", "id root true\nfalse");
+        plans.extend(plan("b.conf", "false\nid root false"));
+        let error = rukinia_check_test_ids(&plans, &TestSelection::default()).unwrap_err();
+        assert_eq!(
+            error.system_error_message,
+            "Duplicate test id 'root' at a.conf:1 and b.conf:2"
+        );
+
+        let plans = plan("a.conf", "if id net true\n    id net true\nend\n");
+        assert!(rukinia_check_test_ids(&plans, &TestSelection::default()).is_ok());
+        let problems = rukinia_check_test_plans(&plans, &RukiniaVariables::default());
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].message, "The test of 'if' cannot have an id");
+
+        let plans = plan("a.conf", "if true\n    id net true\nend\nid net false\n");
+        let error = rukinia_check_test_ids(&plans, &TestSelection::default()).unwrap_err();
+        assert_eq!(
+            error.system_error_message,
+            "Duplicate test id 'net' at a.conf:2 and a.conf:4"
+        );
+
+        let error = rukinia_check_test_ids(
+            &plan("a.conf", "id a true and id b false"),
+            &TestSelection::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.system_error_message,
+            "Test at a.conf:1 has several ids: a, b"
+        );
+    }
+
+    #[test]
+    fn test_check_test_plans() {
+        let defaults = RukiniaVariables::default();
+        assert!(
+            rukinia_check_test_plans(&plan("a.conf", "true\nnot false\n"), &defaults).is_empty()
+        );
+
+        let mut plans = plan("a.conf", "true\nunknown_task\n(true\ntrue x and false\n");
+        plans.extend(plan("b.conf", "id x true\nid x false\nid y id z true\n"));
+        let problems = rukinia_check_test_plans(&plans, &defaults);
+        let locations: Vec<String> = problems
+            .iter()
+            .map(|problem| format!("{}:{}:{}", problem.path, problem.line, problem.column))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                "a.conf:2:1",
+                "a.conf:3:1",
+                "a.conf:4:1",
+                "b.conf:2:4",
+                "b.conf:3:9"
+            ]
+        );
+        assert_eq!(problems[0].message, "Unknown task 'unknown_task'");
+        assert_eq!(problems[1].message, "Missing ')' to close '('");
+        assert_eq!(
+            problems[2].message,
+            "true: Expected no argument, got 1 argument"
+        );
+        assert_eq!(
+            problems[3].message,
+            "Duplicate test id 'x', first used at b.conf:1"
+        );
+        assert_eq!(problems[4].message, "Test already has the id 'y'");
+        assert_eq!(
+            problems[3].to_string(),
+            "b.conf:2:4: Duplicate test id 'x', first used at b.conf:1\n    id x false\n       ^"
+        );
+
+        let problems = rukinia_check_test_plans(
+            &plan(
+                "c.conf",
+                "# Checks\n\ntrue # always\ntrue and \\\n  false x\n",
+            ),
+            &defaults,
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(
+            problems[0].to_string(),
+            "c.conf:4:10: false: Expected no argument, got 1 argument\n    true and false x\n             ^"
+        );
+    }
+
+    #[test]
+    fn test_describe_test_plans() {
+        let defaults = RukiniaVariables::default();
+        let plans = plan(
+            "a.conf",
+            "id t as \"always\" true\nnot (true and false x) or false\nnot (false) or false\n",
+        );
+        let descriptions =
+            rukinia_describe_test_plans(&plans, &TestSelection::default(), &defaults);
+        assert_eq!(descriptions.len(), 3);
+
+        assert_eq!(
+            descriptions[0].to_string(),
+            "a.conf:1: id t as \"always\" true\n    id \"t\" as \"always\" true []\n    => [t] always"
+        );
+        assert!(descriptions[1].error.is_some());
+        assert_eq!(
+            descriptions[2].nodes,
+            vec!["not (", "  false []", ")", "or", "false []"]
+        );
+        assert_eq!(descriptions[2].label, "not (false) or false");
+
+        let only = TestSelection {
+            only: vec!["t".to_string()],
+            exclude: Vec::new(),
+        };
+        let descriptions = rukinia_describe_test_plans(&plans, &only, &defaults);
+        assert_eq!(descriptions.len(), 1);
+        assert_eq!(descriptions[0].line, 1);
+    }
+
+    #[test]
+    fn test_plan_variables() {
+        let defines = RukiniaVariables::new(vec![("B".to_string(), "true".to_string())]);
+        let mut plans = plan(
+            "a.conf",
+            "set A \"always ${B}\"\nset B false\nas \"${A}\" rukinia_cmd ${B} x\nset 1 x\n",
+        );
+        plans.extend(plan(
+            "b.conf",
+            "as ${A} true\nrukinia_cmd ${env:RUKINIA_UNDEFINED_VARIABLE}\n",
+        ));
+
+        let descriptions = rukinia_describe_test_plans(&plans, &TestSelection::default(), &defines);
+        assert_eq!(descriptions.len(), 4);
+        assert_eq!(descriptions[0].line, 3);
+        assert_eq!(
+            descriptions[0].nodes,
+            vec!["as \"always true\" rukinia_cmd [\"true\", \"x\"]"]
+        );
+        assert_eq!(descriptions[0].label, "always true");
+        assert_eq!(
+            descriptions[1].error.as_ref().unwrap().system_error_message,
+            "Invalid variable name '1' at column 5"
+        );
+        assert_eq!(
+            descriptions[2].error.as_ref().unwrap().system_error_message,
+            "Undefined variable 'A'"
+        );
+        assert!(descriptions[3].error.is_some());
+
+        let problems: Vec<String> = rukinia_check_test_plans(&plans, &defines)
+            .iter()
+            .map(|problem| format!("{}:{}: {}", problem.path, problem.line, problem.column))
+            .collect();
+        assert_eq!(problems, vec!["a.conf:4: 5", "b.conf:1: 1", "b.conf:2: 1"]);
+    }
+
+    #[test]
+    fn test_plan_blocks() {
+        let defaults = RukiniaVariables::default();
+        let plans = plan(
+            "a.conf",
+            "if rukinia_kmod ${MODULE}\n    id w true\nelse\n    false x\nend\nif unknown_task\nend\n",
+        );
+        let problems: Vec<String> = rukinia_check_test_plans(&plans, &defaults)
+            .iter()
+            .map(|problem| format!("{}:{}: {}", problem.line, problem.column, problem.message))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "1:4: Undefined variable 'MODULE'",
+                "4:5: false: Expected no argument, got 1 argument",
+                "6:4: Unknown task 'unknown_task'",
+            ]
+        );
+
+        let defines = RukiniaVariables::new(vec![("MODULE".to_string(), "wl18xx".to_string())]);
+        let descriptions = rukinia_describe_test_plans(&plans, &TestSelection::default(), &defines);
+        assert_eq!(descriptions.len(), 4);
+        assert_eq!(
+            descriptions[0].label,
+            "if Checking kernel module wl18xx loaded"
+        );
+        assert_eq!(descriptions[1].label, "[w] true");
+        assert!(descriptions[2].error.is_some());
+        assert_eq!(descriptions[3].line, 6);
+
+        let only = TestSelection {
+            only: vec!["w".to_string()],
+            exclude: Vec::new(),
+        };
+        let descriptions = rukinia_describe_test_plans(&plans, &only, &defines);
+        assert_eq!(descriptions.len(), 1);
+        assert_eq!(descriptions[0].line, 2);
+
+        let contents = "if true\n  else\n  else x\nend\nend\nif false\n";
+        let problems: Vec<String> = rukinia_check_test_plans(&plan("b.conf", contents), &defaults)
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "b.conf:3:3: Several 'else' for the 'if' at b.conf:1\n      else x\n      ^",
+                "b.conf:3:8: Unexpected 'x'\n      else x\n           ^",
+                "b.conf:5:1: 'end' without 'if'\n    end\n    ^",
+                "b.conf:6:1: Missing 'end' to close the 'if'\n    if false\n    ^",
+            ]
+        );
+
+        let path = std::env::temp_dir().join(format!("rukinia-blocks-{}.conf", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        let error = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(rukinia_run_analysis(
+                &[path.to_string_lossy().into_owned()],
+                &mut Vec::new(),
+                &TestSelection::default(),
+                &defaults,
+            ))
+            .err()
+            .unwrap();
+        assert_eq!(error.system_error.as_deref(), Some("Invalid block"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_execute_conditions() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let execute = |test: &str| runtime.block_on(rukinia_execute_single_test(test));
+
+        let result = execute("skip_if (true) as \"never\" false").unwrap();
+        assert!(result.result_type == RukiniaResultType::TestSkip);
+        assert_eq!(result.label, "never");
+        assert_eq!(result.details.as_deref(), Some("skip_if (true) passed"));
+
+        let result = execute("only_if (false or false) id f false").unwrap();
+        assert!(result.result_type == RukiniaResultType::TestSkip);
+        assert_eq!(result.id.as_deref(), Some("f"));
+        assert_eq!(
+            result.details.as_deref(),
+            Some("only_if (false or false) failed")
+        );
+
+        let result = execute("skip_if (false) only_if (true) false").unwrap();
+        assert!(result.result_type == RukiniaResultType::TestFail);
+
+        let error = execute("skip_if (true) false x").err().unwrap();
+        assert_eq!(error.system_error.as_deref(), Some("Invalid arguments"));
+    }
+
+    #[test]
+    fn test_check_result_formats() {
+        let mut formats = vec![
+            ResultFormat::new(FormatOutput::JUnit, "rukinia.xml".to_string()),
+            ResultFormat::new(FormatOutput::Json, "rukinia.json".to_string()),
+        ];
+        assert!(rukinia_check_result_formats(&formats).is_ok());
+
+        formats.push(ResultFormat::new(
+            FormatOutput::TextFile,
+            "rukinia.xml".to_string(),
+        ));
+        let error = rukinia_check_result_formats(&formats).unwrap_err();
+        assert_eq!(
+            error.system_error_message,
+            "junit and text reports are both written to rukinia.xml"
+        );
+
+        let mut append = ResultFormat::new(FormatOutput::JUnit, "append.xml".to_string());
+        append.options.mode = ReportMode::Append;
+        let error = rukinia_check_result_formats(&[append]).unwrap_err();
+        assert_eq!(
+            error.system_error_message,
+            "junit reports cannot be appended to, use the truncate or timestamped mode"
+        );
+    }
+}
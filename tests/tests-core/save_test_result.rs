@@ -0,0 +1,386 @@
+#[cfg(test)]
+mod save_test_result {
+
+    use rukinia::core::rukinia_result::{
+        format_timestamp, RukiniaError, RukiniaResultEntry, RukiniaResultType, RukiniaRunInfo,
+        RukiniaTaskCall,
+    };
+    use rukinia::core::save_test_result::{
+        lava_sanitize, xml_escape, FormatOutput, JUnitReport, JsonReport, JsonTestResult,
+        LavaResult, LavaWriter, ReportMode, ResultFormatOptions, TapDirective, TapWriter,
+    };
+    use rukinia::core::syntax::{Syntax, SyntaxForTrait};
+    use std::str::FromStr;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn run() -> RukiniaRunInfo {
+        RukiniaRunInfo {
+            run_id: "r1".to_string(),
+            start_time: UNIX_EPOCH + Duration::from_secs(1714657512),
+            hostname: "board".to_string(),
+            version: "0.1.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(
+            xml_escape("a \"b\" <c> & 'd'\u{1}"),
+            "a &quot;b&quot; &lt;c&gt; &amp; &apos;d&apos;"
+        );
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let mut report = JUnitReport::default();
+        let mut entry = RukiniaResultEntry::new(
+            RukiniaResultType::TestSuccess,
+            "Running \"ls\" is successful".to_string(),
+        );
+        entry.duration = Duration::from_millis(1500);
+        report.add_result(&entry);
+        report.add_result(&RukiniaResultEntry::new(
+            RukiniaResultType::TestFail,
+            "Checking user a<b exists".to_string(),
+        ));
+        report.add_error(
+            &RukiniaError::new(
+                "rukinia_kmod".to_string(),
+                "Missing module name argument".to_string(),
+                "No module name provided".to_string(),
+            ),
+            Duration::ZERO,
+        );
+
+        let xml = report.to_xml();
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites "));
+        assert!(xml.contains(
+            "<testsuite name=\"rukinia\" tests=\"3\" failures=\"1\" errors=\"1\" skipped=\"0\" time=\"1.500\">"
+        ));
+        assert!(xml.contains(
+            "<testcase name=\"Running &quot;ls&quot; is successful\" classname=\"rukinia\" time=\"1.500\" />"
+        ));
+        assert!(xml.contains(
+            "<failure message=\"Checking user a&lt;b exists\" type=\"TestFail\" />"
+        ));
+        assert!(xml.contains(
+            "<error message=\"No module name provided\" type=\"Missing module name argument\">"
+        ));
+        assert!(xml.ends_with("  </testsuite>\n</testsuites>\n"));
+    }
+
+    #[test]
+    fn test_junit_report_id() {
+        let mut report = JUnitReport::default();
+        let mut entry = RukiniaResultEntry::new(RukiniaResultType::TestSuccess, "true".to_string());
+        entry.id = Some("always".to_string());
+        report.add_result(&entry);
+
+        assert!(report.to_xml().contains(
+            "    <testcase name=\"true\" classname=\"rukinia\" time=\"0.000\">\n\
+             \x20     <properties>\n\
+             \x20       <property name=\"id\" value=\"always\" />\n\
+             \x20     </properties>\n\
+             \x20   </testcase>\n"
+        ));
+    }
+
+    #[test]
+    fn test_report_details_and_metrics() {
+        let mut entry = RukiniaResultEntry::new(RukiniaResultType::TestSuccess, "modem".to_string());
+        entry.details = Some("AT OK".to_string());
+        entry.metrics.insert("latency_ms".to_string(), 12.5);
+
+        let mut report = JUnitReport::default();
+        report.add_result(&entry);
+        assert!(report.to_xml().contains(
+            "    <testcase name=\"modem\" classname=\"rukinia\" time=\"0.000\">\n\
+             \x20     <properties>\n\
+             \x20       <property name=\"metric.latency_ms\" value=\"12.5\" />\n\
+             \x20     </properties>\n\
+             \x20     <system-out>AT OK</system-out>\n\
+             \x20   </testcase>\n"
+        ));
+
+        let json = serde_json::to_value(JsonTestResult::from_result(&entry)).unwrap();
+        assert_eq!(json["details"], "AT OK");
+        assert_eq!(json["metrics"]["latency_ms"], 12.5);
+        let json = serde_json::to_value(JsonTestResult::from_result(
+            &RukiniaResultEntry::new(RukiniaResultType::TestSuccess, "true".to_string()),
+        ))
+        .unwrap();
+        assert!(json.get("details").is_none());
+        assert!(json.get("metrics").is_none());
+
+        let mut tap = TapWriter::new(Vec::new());
+        tap.add_result(&entry).unwrap();
+        let tap = String::from_utf8(tap.into_inner()).unwrap();
+        assert!(tap.ends_with(
+            "ok 1 - modem\n  ---\n  details: \"AT OK\"\n  metrics: {\"latency_ms\":12.5}\n  ...\n"
+        ));
+    }
+
+    #[test]
+    fn test_junit_report_run() {
+        let mut report = JUnitReport::default();
+        report.set_run(&run());
+
+        assert!(report.to_xml().contains(
+            "  <testsuite name=\"rukinia\" tests=\"0\" failures=\"0\" errors=\"0\" skipped=\"0\" time=\"0.000\" timestamp=\"2024-05-02T13:45:12\" hostname=\"board\">\n\
+             \x20   <properties>\n\
+             \x20     <property name=\"run_id\" value=\"r1\" />\n\
+             \x20     <property name=\"rukinia_version\" value=\"0.1.0\" />\n\
+             \x20   </properties>\n"
+        ));
+    }
+
+    #[test]
+    fn test_report_mode() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_secs(951782400)),
+            "2000-02-29T00:00:00Z"
+        );
+
+        assert_eq!(ReportMode::default(), ReportMode::Truncate);
+        assert_eq!(
+            ReportMode::Append.get_path("out/rukinia.csv", &run()),
+            "out/rukinia.csv"
+        );
+        assert_eq!(
+            ReportMode::Timestamped.get_path("out/rukinia.csv", &run()),
+            "out/rukinia-20240502T134512Z.csv"
+        );
+        assert_eq!(
+            ReportMode::Timestamped.get_path("rukinia", &run()),
+            "rukinia-20240502T134512Z"
+        );
+        assert!(FormatOutput::JsonLines.supports_append());
+        assert!(!FormatOutput::Tap.supports_append());
+
+        let mut tap = TapWriter::new(Vec::new());
+        tap.add_run(&run()).unwrap();
+        tap.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(tap.into_inner()).unwrap(),
+            "TAP version 14\n\
+             # run_id: r1\n\
+             # start_time: 2024-05-02T13:45:12Z\n\
+             # hostname: board\n\
+             # rukinia_version: 0.1.0\n\
+             1..0\n"
+        );
+    }
+
+    #[test]
+    fn test_json_test_result() {
+        let syntax = SyntaxForTrait::Arguments(vec![Syntax::Not, Syntax::Id("root".to_string())]);
+        let mut entry = RukiniaResultEntry::new(
+            RukiniaResultType::TestFail,
+            "Checking user root not exists".to_string(),
+        );
+        entry.tasks = vec![RukiniaTaskCall {
+            task: "rukinia_user".to_string(),
+            arguments: vec!["root".to_string()],
+            syntax: syntax.clone(),
+        }];
+        entry.syntax = syntax;
+        entry.id = Some("root".to_string());
+
+        let json = serde_json::to_value(JsonTestResult::from_result(&entry)).unwrap();
+        assert_eq!(json["result"], "FAIL");
+        assert_eq!(json["not"], true);
+        assert_eq!(json["id"], "root");
+        assert!(json["as"].is_null());
+        assert_eq!(json["tasks"][0]["task"], "rukinia_user");
+        assert_eq!(json["tasks"][0]["arguments"][0], "root");
+        assert!(json["error"].is_null());
+    }
+
+    #[test]
+    fn test_json_report() {
+        let mut report = JsonReport::default();
+        report.add_result(&RukiniaResultEntry::new(
+            RukiniaResultType::TestSuccess,
+            "true".to_string(),
+        ));
+        report.add_error(
+            &RukiniaError::new(
+                "rukinia_kmod".to_string(),
+                "Missing module name argument".to_string(),
+                "No module name provided".to_string(),
+            ),
+            Duration::from_millis(250),
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["summary"]["total"], 2);
+        assert_eq!(json["summary"]["passed"], 1);
+        assert_eq!(json["summary"]["errors"], 1);
+        assert_eq!(json["summary"]["duration"], 0.25);
+        assert_eq!(json["tests"][1]["result"], "SYSTEM ERROR");
+        assert_eq!(json["tests"][1]["error"]["command"], "rukinia_kmod");
+        assert_eq!(json["tests"][1]["error"]["message"], "No module name provided");
+    }
+
+    #[test]
+    fn test_skipped_result() {
+        let mut entry = RukiniaResultEntry::new(
+            RukiniaResultType::TestSkip,
+            "Checking interface eth1 is up".to_string(),
+        );
+        entry.details = Some("only_if (Checking interface eth1 exists) failed".to_string());
+
+        let mut junit = JUnitReport::default();
+        junit.add_result(&entry);
+        let xml = junit.to_xml();
+        assert!(xml.contains("failures=\"0\" errors=\"0\" skipped=\"1\""));
+        assert!(
+            xml.contains("<skipped message=\"only_if (Checking interface eth1 exists) failed\" />")
+        );
+
+        let mut json = JsonReport::default();
+        json.add_result(&entry);
+        let json: serde_json::Value = serde_json::from_str(&json.to_json().unwrap()).unwrap();
+        assert_eq!(json["summary"]["skipped"], 1);
+        assert_eq!(json["summary"]["failed"], 0);
+        assert_eq!(json["tests"][0]["result"], "SKIP");
+
+        let mut tap = TapWriter::new(Vec::new());
+        tap.add_result(&entry).unwrap();
+        assert!(String::from_utf8(tap.into_inner()).unwrap().contains(
+            "ok 1 - Checking interface eth1 is up # SKIP only_if (Checking interface eth1 exists) failed\n"
+        ));
+
+        assert_eq!(LavaResult::from(&entry.result_type), LavaResult::Skip);
+    }
+
+    #[test]
+    fn test_result_format_options() {
+        let mut options = ResultFormatOptions::default();
+        assert_eq!(options.suite_name, JUnitReport::SUITE_NAME);
+        assert!(options.pretty);
+
+        options.set("suite_name", "rootfs").unwrap();
+        options.set("pretty", "false").unwrap();
+        options.set("mode", "append").unwrap();
+        assert_eq!(options.suite_name, "rootfs");
+        assert!(!options.pretty);
+        assert_eq!(options.mode, ReportMode::Append);
+        assert!(options.set("pretty", "maybe").is_err());
+        assert!(options.set("colour", "red").is_err());
+
+        let mut junit = JUnitReport::new(&options.suite_name);
+        let mut json = JsonReport::new(options.pretty);
+        let entry = RukiniaResultEntry::new(RukiniaResultType::TestSuccess, "true".to_string());
+        junit.add_result(&entry);
+        json.add_result(&entry);
+
+        let xml = junit.to_xml();
+        assert!(xml.contains("<testcase name=\"true\" classname=\"rootfs\" time=\"0.000\" />"));
+        assert!(!json.to_json().unwrap().contains('\n'));
+        assert_eq!(FormatOutput::TextFile.extension(), "txt");
+        assert_eq!(FormatOutput::JsonLines.to_string(), "jsonl");
+        assert_eq!(
+            FormatOutput::from_str(&FormatOutput::TextFile.to_string()),
+            Ok(FormatOutput::TextFile)
+        );
+    }
+
+    #[test]
+    fn test_tap_writer() {
+        let mut tap = TapWriter::new(Vec::new());
+        tap.add_result(&RukiniaResultEntry::new(
+            RukiniaResultType::TestSuccess,
+            "Checking # of users".to_string(),
+        ))
+        .unwrap();
+        tap.add_test(
+            true,
+            "Checking interface eth0 is up",
+            Some(TapDirective::Skip("network disabled".to_string())),
+            &[],
+        )
+        .unwrap();
+        tap.add_test(
+            false,
+            "Checking wl18xx is loaded",
+            Some(TapDirective::Todo("driver not packaged yet".to_string())),
+            &[],
+        )
+        .unwrap();
+        tap.add_error(
+            &RukiniaError::new_with_short_description(
+                "rukinia_bad x".to_string(),
+                "Unknown \"task\"".to_string(),
+            ),
+            Duration::from_millis(3),
+        )
+        .unwrap();
+        tap.finish().unwrap();
+
+        assert_eq!(
+            String::from_utf8(tap.into_inner()).unwrap(),
+            "TAP version 14\n\
+             ok 1 - Checking \\# of users\n\
+             ok 2 - Checking interface eth0 is up # SKIP network disabled\n\
+             not ok 3 - Checking wl18xx is loaded # TODO driver not packaged yet\n\
+             not ok 4 - rukinia_bad x\n\
+             \x20 ---\n\
+             \x20 message: \"Unknown \\\"task\\\"\"\n\
+             \x20 severity: error\n\
+             \x20 command: \"rukinia_bad x\"\n\
+             \x20 duration_ms: 3\n\
+             \x20 ...\n\
+             1..4\n"
+        );
+    }
+
+    #[test]
+    fn test_lava_test_case_id() {
+        let mut lava = LavaWriter::new(Vec::new());
+        assert_eq!(lava.test_case_id("rukinia_user root"), "rukinia_user_root");
+        assert_eq!(
+            lava.test_case_id("rukinia_user root"),
+            "rukinia_user_root_2"
+        );
+        assert_eq!(
+            lava.test_case_id("  (check: /dev/i2c-1) "),
+            "check_dev_i2c-1"
+        );
+        assert_eq!(lava.test_case_id("!!!"), "test");
+        assert_eq!(lava.test_case_id("_boot__check"), "_boot__check");
+        assert_eq!(lava.test_case_id("boot_check"), "boot_check");
+        assert_eq!(lava.test_case_id("boot check"), "boot_check_2");
+        assert_eq!(lava_sanitize(" eth0_ (up)"), "eth0__up");
+    }
+
+    #[test]
+    fn test_lava_writer() {
+        let mut lava = LavaWriter::new(Vec::new());
+        lava.start_test_set("rukinia").unwrap();
+        lava.start_test_case("root_exists").unwrap();
+        lava.end_test_case(
+            "root_exists",
+            LavaResult::from(&RukiniaResultType::TestSuccess),
+            Duration::from_millis(12),
+        )
+        .unwrap();
+        lava.start_test_case("bad_task").unwrap();
+        lava.end_test_case("bad_task", LavaResult::Unknown, Duration::ZERO)
+            .unwrap();
+        lava.stop_test_set().unwrap();
+
+        assert_eq!(
+            String::from_utf8(lava.into_inner()).unwrap(),
+            "<LAVA_SIGNAL_TESTSET START rukinia>\n\
+             <LAVA_SIGNAL_STARTTC root_exists>\n\
+             <LAVA_SIGNAL_ENDTC root_exists>\n\
+             <LAVA_SIGNAL_TESTCASE TEST_CASE_ID=root_exists RESULT=pass MEASUREMENT=0.012 UNITS=seconds>\n\
+             <LAVA_SIGNAL_STARTTC bad_task>\n\
+             <LAVA_SIGNAL_ENDTC bad_task>\n\
+             <LAVA_SIGNAL_TESTCASE TEST_CASE_ID=bad_task RESULT=unknown MEASUREMENT=0.000 UNITS=seconds>\n\
+             <LAVA_SIGNAL_TESTSET STOP>\n"
+        );
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod variables {
+
+    use rukinia::core::variables::{is_variable_name, rukinia_parse_define, RukiniaVariables};
+
+    #[test]
+    fn test_variables_expand() {
+        let mut variables = RukiniaVariables::new(vec![("BOARD".to_string(), "imx8".to_string())]);
+        variables.set("IFACE", "eth0").unwrap();
+        variables.set("LABEL", "${BOARD} on ${IFACE}").unwrap();
+        variables.set("BOARD", "am62").unwrap();
+
+        assert_eq!(variables.get("BOARD"), Some("imx8"));
+        assert_eq!(variables.get("LABEL"), Some("imx8 on eth0"));
+        assert_eq!(
+            variables
+                .expand("${IFACE}:${LABEL} $$5 $${IFACE} $ $x")
+                .unwrap(),
+            "eth0:imx8 on eth0 $5 ${IFACE} $ $x"
+        );
+
+        std::env::set_var("RUKINIA_TEST_VARIABLE", "value");
+        assert_eq!(
+            variables.expand("${env:RUKINIA_TEST_VARIABLE}").unwrap(),
+            "value"
+        );
+        assert_eq!(
+            variables.expand("${env:RUKINIA_UNDEFINED_VARIABLE}"),
+            Err("Undefined environment variable 'RUKINIA_UNDEFINED_VARIABLE'".to_string())
+        );
+        assert_eq!(
+            variables.expand("${UNKNOWN}"),
+            Err("Undefined variable 'UNKNOWN'".to_string())
+        );
+        assert_eq!(
+            variables.expand("${IFACE"),
+            Err("Missing '}' to close '${' in '${IFACE'".to_string())
+        );
+        assert_eq!(
+            variables.set("1X", "value"),
+            Err("Invalid variable name '1X'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_variable_names_and_defines() {
+        assert!(is_variable_name("_IFACE_2"));
+        assert!(!is_variable_name("2IFACE"));
+        assert!(!is_variable_name("env:HOME"));
+        assert!(!is_variable_name(""));
+
+        assert_eq!(
+            rukinia_parse_define("IFACE=eth0=1"),
+            Ok(("IFACE".to_string(), "eth0=1".to_string()))
+        );
+        assert_eq!(
+            rukinia_parse_define("IFACE"),
+            Err("invalid definition 'IFACE', expected NAME=VALUE".to_string())
+        );
+        assert_eq!(
+            rukinia_parse_define("I-FACE=eth0"),
+            Err("invalid variable name 'I-FACE'".to_string())
+        );
+    }
+}
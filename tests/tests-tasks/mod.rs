@@ -0,0 +1,10 @@
+use rukinia::tasks::task::RukiniaProcess;
+
+/// Runs the task on a current thread runtime.
+pub fn run<T: RukiniaProcess>(task: &mut T) {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(task.run())
+        .unwrap();
+}
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod rukinia_cmd {
 
+    use crate::tests_tasks::run;
     use rukinia::core::rukinia_result::RukiniaResultType;
     use rukinia::core::syntax::{Syntax, SyntaxForTrait};
     use rukinia::tasks::filesystem::rukinia_cmd::RukiniaCmd;
@@ -19,7 +20,8 @@ mod rukinia_cmd {
             SyntaxForTrait::Arguments(Vec::new()),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestSuccess);
         assert!(rukinia_cmd.display_format() == "Running \"ls\" is successful");
     }
@@ -31,7 +33,8 @@ mod rukinia_cmd {
             SyntaxForTrait::Arguments(vec![Syntax::Not]),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestFail);
         assert!(rukinia_cmd.display_format() == "Running \"ls\" is not successful");
     }
@@ -43,7 +46,8 @@ mod rukinia_cmd {
             SyntaxForTrait::Arguments(Vec::new()),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestFail);
         assert!(
             rukinia_cmd.display_format()
@@ -51,6 +55,31 @@ mod rukinia_cmd {
         );
     }
 
+    #[test]
+    fn test_rukinia_cmd_only_runs_when_run() {
+        let path = std::env::temp_dir().join(format!("rukinia-cmd-{}", std::process::id()));
+        let mut rukinia_cmd = RukiniaCmd::new(
+            vec!["touch".to_string(), path.to_string_lossy().into_owned()],
+            SyntaxForTrait::Arguments(Vec::new()),
+        )
+        .unwrap();
+        assert!(!path.exists());
+
+        run(&mut rukinia_cmd);
+        assert!(path.exists());
+        assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestSuccess);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_rukinia_cmd_check_arguments() {
+        assert_eq!(
+            RukiniaCmd::check_arguments(&[]),
+            Err("Expected <command> [argument...], got no argument".to_string())
+        );
+        assert!(RukiniaCmd::check_arguments(&["ls".to_string(), "-l".to_string()]).is_ok());
+    }
+
     #[test]
     fn test_rukinia_cmd_get_command() {
         assert_eq!(RukiniaCmd::get_rukinia_command(), "rukinia_cmd");
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod rukinia_false {
 
+    use crate::tests_tasks::run;
     use rukinia::core::rukinia_result::RukiniaResultType;
     use rukinia::core::syntax::{Syntax, SyntaxForTrait};
     use rukinia::tasks::bool::rukinia_false::RukiniaFalse;
@@ -13,7 +14,8 @@ mod rukinia_false {
             SyntaxForTrait::Arguments(Vec::new()),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestFail);
         assert!(rukinia_cmd.display_format() == "false");
     }
@@ -25,7 +27,8 @@ mod rukinia_false {
             SyntaxForTrait::Arguments(vec![Syntax::Not]),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestSuccess);
         assert!(rukinia_cmd.display_format() == "not false");
     }
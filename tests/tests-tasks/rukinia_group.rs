@@ -1,8 +1,7 @@
 #[cfg(test)]
 mod rukinia_group {
 
-    use rukinia::core::rukinia_result::RukiniaResultType;
-    use rukinia::core::syntax::{Syntax, SyntaxForTrait};
+    use rukinia::core::syntax::SyntaxForTrait;
     use rukinia::tasks::user::rukinia_group::RukiniaGroup;
     use rukinia::tasks::task::RukiniaProcess;
 
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod rukinia_plugin {
+
+    use rukinia::core::rukinia_result::{RukiniaError, RukiniaResultEntry, RukiniaResultType};
+    use rukinia::core::syntax::{Syntax, SyntaxForTrait};
+    use rukinia::tasks::plugin::rukinia_plugin::{
+        rukinia_find_plugins, rukinia_register_plugins, RukiniaPlugin,
+    };
+    use rukinia::tasks::task::{RukiniaAllTasks, RukiniaTask};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use std::time::{Duration, Instant};
+
+    const MODEM: &str = r#"#!/bin/sh
+case "$1" in
+    ok) echo '{"result": "pass", "label": "Modem answers", "details": "AT OK", "metrics": {"latency_ms": 12}}' ;;
+    error) echo '{"result": "error", "details": "No modem"}' ;;
+    absent) echo '{"result": "skip", "details": "No modem slot"}' ;;
+    crash) echo "segfault" >&2; exit 3 ;;
+    hang) exec sleep 10 ;;
+    *) echo '{"result": "fail"}' ;;
+esac
+"#;
+
+    fn plugin_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("rukinia-plugins-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn write_plugin(directory: &Path, file_name: &str, script: &str, mode: u32) {
+        let path = directory.join(file_name);
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    fn run(
+        task: &RukiniaAllTasks,
+        arguments: &[&str],
+        syntax: Vec<Syntax>,
+    ) -> Result<RukiniaResultEntry, RukiniaError> {
+        let task = task.build(
+            arguments
+                .iter()
+                .map(|argument| argument.to_string())
+                .collect(),
+            SyntaxForTrait::Arguments(syntax),
+        )?;
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(task.run())
+    }
+
+    #[test]
+    fn test_find_plugins() {
+        let first = plugin_directory("first");
+        let second = plugin_directory("second");
+        write_plugin(&first, "rukinia-task-modem", MODEM, 0o755);
+        write_plugin(&first, "rukinia-task-notes", MODEM, 0o644);
+        write_plugin(&first, "modem", MODEM, 0o755);
+        write_plugin(&second, "rukinia-task-modem", MODEM, 0o755);
+        write_plugin(&second, "rukinia-task-gps", MODEM, 0o755);
+
+        let paths = vec![
+            first.to_string_lossy().into_owned(),
+            "/nonexistent/rukinia".to_string(),
+            second.to_string_lossy().into_owned(),
+        ];
+        let entries = rukinia_find_plugins(&paths);
+        let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, vec!["rukinia_modem", "rukinia_gps"]);
+        assert_eq!(
+            entries[0].description,
+            format!("Plugin {}", first.join("rukinia-task-modem").display())
+        );
+        assert_eq!(entries[0].feature.as_deref(), Some("plugin"));
+
+        fs::remove_dir_all(first).unwrap();
+        fs::remove_dir_all(second).unwrap();
+    }
+
+    #[test]
+    fn test_plugin_run() {
+        let directory = plugin_directory("run");
+        write_plugin(&directory, "rukinia-task-plugin_test_modem", MODEM, 0o755);
+        write_plugin(&directory, "rukinia-task-user", MODEM, 0o755);
+
+        let registered = rukinia_register_plugins(&[directory.to_string_lossy().into_owned()]);
+        assert_eq!(registered, vec!["rukinia_plugin_test_modem"]);
+        let task = RukiniaAllTasks::from_str("rukinia_plugin_test_modem").unwrap();
+
+        let result = run(&task, &["ok"], Vec::new()).unwrap();
+        assert!(result.result_type == RukiniaResultType::TestSuccess);
+        assert_eq!(result.label, "Modem answers");
+        assert_eq!(result.details.as_deref(), Some("AT OK"));
+        assert_eq!(result.metrics["latency_ms"], 12.0);
+
+        let result = run(&task, &["busy"], vec![Syntax::Not]).unwrap();
+        assert!(result.result_type == RukiniaResultType::TestSuccess);
+        assert_eq!(result.label, "not rukinia_plugin_test_modem busy");
+
+        let result = run(&task, &["absent"], vec![Syntax::Not]).unwrap();
+        assert!(result.result_type == RukiniaResultType::TestSkip);
+        assert_eq!(result.details.as_deref(), Some("No modem slot"));
+
+        let error = run(&task, &["error"], Vec::new()).err().unwrap();
+        assert_eq!(error.system_error.as_deref(), Some("Plugin error"));
+        assert_eq!(error.system_error_message, "No modem");
+
+        let error = run(&task, &["crash"], Vec::new()).err().unwrap();
+        assert_eq!(error.system_error.as_deref(), Some("Invalid plugin output"));
+        assert!(error.system_error_message.ends_with(": segfault"));
+
+        let plugin = RukiniaPlugin::new(
+            "rukinia_plugin_test_modem",
+            &directory.join("rukinia-task-plugin_test_modem"),
+            vec!["hang".to_string()],
+            SyntaxForTrait::Arguments(Vec::new()),
+        )
+        .with_timeout(Duration::from_millis(200));
+        let start = Instant::now();
+        let error = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(RukiniaTask::from(plugin).run())
+            .err()
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(error.system_error.as_deref(), Some("Plugin timed out"));
+        assert!(error
+            .system_error_message
+            .ends_with("rukinia-task-plugin_test_modem did not exit within 0.2 seconds"));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
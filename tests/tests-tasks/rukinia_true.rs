@@ -1,10 +1,12 @@
 #[cfg(test)]
 mod rukinia_true {
 
+    use crate::tests_tasks::run;
     use rukinia::core::rukinia_result::RukiniaResultType;
     use rukinia::core::syntax::{Syntax, SyntaxForTrait};
     use rukinia::tasks::bool::rukinia_true::RukiniaTrue;
-    use rukinia::tasks::task::RukiniaProcess;
+    use rukinia::tasks::task::{RukiniaAllTasks, RukiniaProcess};
+    use std::str::FromStr;
 
     #[test]
     fn test_rukinia_true() {
@@ -13,7 +15,8 @@ mod rukinia_true {
             SyntaxForTrait::Arguments(Vec::new()),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestSuccess);
         assert!(rukinia_cmd.display_format() == "true");
     }
@@ -25,7 +28,8 @@ mod rukinia_true {
             SyntaxForTrait::Arguments(vec![Syntax::Not]),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestFail);
         assert!(rukinia_cmd.display_format() == "not true");
     }
@@ -34,4 +38,28 @@ mod rukinia_true {
     fn test_rukinia_true_get_command() {
         assert_eq!(RukiniaTrue::get_rukinia_command(), "true");
     }
+
+    #[test]
+    fn test_rukinia_true_build() {
+        assert!(RukiniaAllTasks::from_str("true")
+            .unwrap()
+            .build(
+                vec!["root".to_string()],
+                SyntaxForTrait::Arguments(Vec::new())
+            )
+            .is_err());
+
+        let task = RukiniaAllTasks::from_str("true")
+            .unwrap()
+            .build(Vec::new(), SyntaxForTrait::Arguments(vec![Syntax::Not]))
+            .unwrap();
+        assert_eq!(task.display_format(), "not true");
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let result = runtime.block_on(task.run()).unwrap();
+        assert!(result.result_type == RukiniaResultType::TestFail);
+        assert_eq!(result.label, "not true");
+    }
 }
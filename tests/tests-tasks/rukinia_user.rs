@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod rukinia_user {
 
+    use crate::tests_tasks::run;
     use rukinia::core::rukinia_result::RukiniaResultType;
     use rukinia::core::syntax::{Syntax, SyntaxForTrait};
     use rukinia::tasks::user::rukinia_user::RukiniaUser;
@@ -19,7 +20,8 @@ mod rukinia_user {
             SyntaxForTrait::Arguments(Vec::new()),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestSuccess);
         assert!(rukinia_cmd.display_format() == "Checking user root exists");
     }
@@ -31,7 +33,8 @@ mod rukinia_user {
             SyntaxForTrait::Arguments(vec![Syntax::Not]),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestFail);
         assert!(rukinia_cmd.display_format() == "Checking user root not exists");
     }
@@ -43,7 +46,8 @@ mod rukinia_user {
             SyntaxForTrait::Arguments(Vec::new()),
         );
         assert!(result.is_ok());
-        let rukinia_cmd = result.unwrap();
+        let mut rukinia_cmd = result.unwrap();
+        run(&mut rukinia_cmd);
         assert!(rukinia_cmd.get_result().result_type == RukiniaResultType::TestFail);
         assert!(
             rukinia_cmd.display_format()
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod task {
+
+    use rukinia::core::rukinia_result::{RukiniaError, RukiniaResultEntry, RukiniaResultType};
+    use rukinia::core::syntax::SyntaxForTrait;
+    use rukinia::tasks::registry::{rukinia_register_task, RukiniaTaskEntry, RukiniaTaskRegistry};
+    use rukinia::tasks::task::{check_argument_count, RukiniaAllTasks, RukiniaProcess};
+    use std::str::FromStr;
+
+    /// Task defined outside of rukinia, succeeding if its argument is "ready".
+    struct BoardState {
+        arguments: Vec<String>,
+        syntax: SyntaxForTrait,
+        result: RukiniaResultEntry,
+    }
+
+    impl RukiniaProcess for BoardState {
+        fn get_rukinia_command() -> &'static str {
+            "board_state"
+        }
+
+        fn get_description() -> &'static str {
+            "Checks the state of the board"
+        }
+
+        fn get_synopsis() -> &'static str {
+            "<state>"
+        }
+
+        fn check_arguments(arguments: &[String]) -> Result<(), String> {
+            check_argument_count(arguments, 1, Some(1), Self::get_synopsis())
+        }
+
+        fn new(arguments: Vec<String>, syntax: SyntaxForTrait) -> Result<Self, RukiniaError> {
+            Self::validate_arguments(&arguments)?;
+            Ok(BoardState {
+                arguments,
+                syntax,
+                result: RukiniaResultEntry::new(RukiniaResultType::TestFail, String::new()),
+            })
+        }
+
+        async fn run(&mut self) -> Result<(), RukiniaError> {
+            if self.arguments[0] == "ready" {
+                self.result.result_type = RukiniaResultType::TestSuccess;
+            }
+            self.apply_syntax();
+            Ok(())
+        }
+
+        fn get_result(&self) -> RukiniaResultEntry {
+            self.result.clone()
+        }
+
+        fn set_result(&mut self, result: RukiniaResultEntry) {
+            self.result = result;
+        }
+
+        fn display_format(&self) -> String {
+            format!("Board is {}", self.arguments[0])
+        }
+
+        fn get_syntax(&self) -> SyntaxForTrait {
+            self.syntax.clone()
+        }
+    }
+
+    #[test]
+    fn test_builtin_tasks() {
+        let registry = RukiniaTaskRegistry::with_builtin_tasks();
+        let names: Vec<&str> = registry
+            .tasks()
+            .iter()
+            .map(|task| task.name.as_str())
+            .collect();
+        assert!(names.ends_with(&["true", "false"]));
+
+        let entry = registry.get("true").unwrap();
+        assert_eq!(entry.description, "Always succeeds");
+        assert_eq!(entry.synopsis, "");
+        assert_eq!(entry.feature, None);
+        assert!(entry.check_arguments(&["x".to_string()]).is_err());
+
+        #[cfg(feature = "user")]
+        {
+            let entry = registry.get("rukinia_user").unwrap();
+            assert_eq!(entry.synopsis, "<username>");
+            assert_eq!(entry.feature.as_deref(), Some("user"));
+        }
+    }
+
+    #[test]
+    fn test_registry_register() {
+        let mut registry = RukiniaTaskRegistry::default();
+        registry
+            .register(RukiniaTaskEntry::of::<BoardState>())
+            .unwrap();
+        assert_eq!(registry.tasks().len(), 1);
+
+        let error = registry
+            .register(RukiniaTaskEntry::of::<BoardState>())
+            .unwrap_err();
+        assert_eq!(
+            error.system_error_message,
+            "The task 'board_state' is already registered"
+        );
+
+        let mut entry = RukiniaTaskEntry::of::<BoardState>();
+        entry.name = "board state".to_string();
+        let error = registry.register(entry).unwrap_err();
+        assert_eq!(
+            error.system_error_message,
+            "The task name must be a single word"
+        );
+    }
+
+    #[test]
+    fn test_register_task() {
+        assert!(RukiniaAllTasks::from_str("board_state").is_err());
+        rukinia_register_task(RukiniaTaskEntry::of::<BoardState>().with_feature("board")).unwrap();
+
+        let task = RukiniaAllTasks::from_str("board_state").unwrap();
+        assert!(RukiniaAllTasks::all().contains(&task));
+        assert_eq!(task.get_entry().feature.as_deref(), Some("board"));
+        assert_eq!(
+            task.check_arguments(&[]).unwrap_err(),
+            "Expected <state>, got no argument"
+        );
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let result = runtime
+            .block_on(task.execute(
+                vec!["ready".to_string()],
+                SyntaxForTrait::Arguments(Vec::new()),
+            ))
+            .unwrap();
+        assert!(result.result_type == RukiniaResultType::TestSuccess);
+        assert_eq!(result.label, "Board is ready");
+    }
+}
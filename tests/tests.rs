@@ -1,9 +1,17 @@
+#![allow(clippy::module_inception)]
+
+#[path = "tests-tasks/mod.rs"]
+mod tests_tasks;
+
+#[cfg(feature = "filesystem")]
 #[path = "tests-tasks/rukinia_cmd.rs"]
 mod rukinia_cmd;
 
+#[cfg(feature = "user")]
 #[path = "tests-tasks/rukinia_group.rs"]
 mod rukinia_group;
 
+#[cfg(feature = "user")]
 #[path = "tests-tasks/rukinia_user.rs"]
 mod rukinia_user;
 
@@ -12,3 +20,40 @@ mod rukinia_false;
 
 #[path = "tests-tasks/rukinia_true.rs"]
 mod rukinia_true;
+
+#[cfg(feature = "plugin")]
+#[path = "tests-tasks/rukinia_plugin.rs"]
+mod rukinia_plugin;
+
+#[path = "tests-tasks/task.rs"]
+mod task;
+
+#[path = "tests-core/cli.rs"]
+mod cli;
+
+#[path = "tests-core/configuration.rs"]
+mod configuration;
+
+#[path = "tests-core/lexer.rs"]
+mod lexer;
+
+#[path = "tests-core/parser.rs"]
+mod parser;
+
+#[path = "tests-core/plan.rs"]
+mod plan;
+
+#[path = "tests-core/reporter.rs"]
+mod reporter;
+
+#[path = "tests-core/run_tasks.rs"]
+mod run_tasks;
+
+#[path = "tests-core/rukinia_result.rs"]
+mod rukinia_result;
+
+#[path = "tests-core/save_test_result.rs"]
+mod save_test_result;
+
+#[path = "tests-core/variables.rs"]
+mod variables;